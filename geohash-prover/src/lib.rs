@@ -1,3 +1,8 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
 use ark_bn254::Bn254;
 use ark_bn254::Fr;
 use ark_ff::PrimeField;
@@ -12,6 +17,9 @@ use rand::RngCore;
 pub struct CompareCircuit<F: PrimeField> {
     pub shorter: Option<Vec<F>>,
     pub larger: Option<Vec<F>>,
+    /// Public input tying the proof to its submitter, so it can't be re-randomised and
+    /// replayed by someone else
+    pub binding: F,
 }
 
 impl CompareCircuit<Fr> {
@@ -19,15 +27,31 @@ impl CompareCircuit<Fr> {
         Self {
             shorter: Some(shorter),
             larger: Some(larger),
+            binding: Fr::default(),
         }
     }
 
     pub fn new_from_str<'a>(shorter: &'a str, larger: &'a str) -> Self {
+        Self::new(
+            PrimeString::<Fr>::from(shorter).into(),
+            PrimeString::<Fr>::from(larger).into(),
+        )
+    }
+
+    /// Binds the proof to `binding`, such as a hash of the challenge and the submitting account.
+    pub fn bound_to(self, binding: &[u8]) -> Self {
         Self {
-            shorter: Some(PrimeString::<Fr>::from(shorter).into()),
-            larger: Some(PrimeString::<Fr>::from(larger).into()),
+            binding: binding_input(binding),
+            ..self
         }
     }
+
+    /// A circuit proving `location` lies within the `challenge` area, or `None` if either isn't
+    /// a valid geohash or the location is outside of the area.
+    pub fn new_from_geohash<'a>(challenge: &'a str, location: &'a str) -> Option<Self> {
+        geohash_utils::contains(challenge.as_bytes(), location.as_bytes())
+            .then(|| Self::new_from_str(challenge, location))
+    }
 }
 
 pub fn setup_groth16<R: RngCore + CryptoRng>(
@@ -42,6 +66,33 @@ pub fn setup_groth16<R: RngCore + CryptoRng>(
     Groth16::<Bn254>::circuit_specific_setup(circuit, rng)
 }
 
+/// Seed of the circuit setup used by development chains.
+pub const DEV_SETUP_SEED: u64 = 0x0067_656f_6861_7368;
+
+/// The keys for challenges of `precision` characters on development chains.
+///
+/// The setup is derived from [`DEV_SETUP_SEED`], so anyone can recover its trapdoor and forge
+/// proofs with it. Never use these keys outside of development.
+#[cfg(feature = "std")]
+pub fn dev_setup(
+    precision: usize,
+) -> Result<
+    (
+        ark_groth16::ProvingKey<Bn254>,
+        ark_groth16::VerifyingKey<Bn254>,
+    ),
+    SynthesisError,
+> {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // The setup only depends on the shape of the circuit, not on the geohashes it compares
+    let geohash = "0".repeat(precision);
+    setup_groth16(
+        &mut StdRng::seed_from_u64(DEV_SETUP_SEED),
+        CompareCircuit::new_from_str(&geohash, &geohash),
+    )
+}
+
 pub fn create_proof<R: RngCore + CryptoRng>(
     pk: &ark_groth16::ProvingKey<Bn254>,
     circuit: CompareCircuit<Fr>,
@@ -50,13 +101,20 @@ pub fn create_proof<R: RngCore + CryptoRng>(
     Groth16::<Bn254>::prove(pk, circuit, rng)
 }
 
-pub fn verify_proof<'a>(
+pub fn verify_proof(
     vk: &ark_groth16::VerifyingKey<Bn254>,
-    public_inputs: &'a str,
+    public_inputs: &str,
+    binding: &[u8],
     proof: &ark_groth16::Proof<Bn254>,
 ) -> Result<bool, SynthesisError> {
-    let public_inputs = &Vec::<Fr>::from(PrimeString::<Fr>::from(public_inputs));
-    Groth16::<Bn254>::verify(vk, public_inputs, proof)
+    let mut public_inputs = Vec::<Fr>::from(PrimeString::<Fr>::from(public_inputs));
+    public_inputs.push(binding_input(binding));
+    Groth16::<Bn254>::verify(vk, &public_inputs, proof)
+}
+
+/// The field element a proof is bound to, the last of its public inputs.
+pub fn binding_input(binding: &[u8]) -> Fr {
+    Fr::from_le_bytes_mod_order(binding)
 }
 
 impl<F: PrimeField> ConstraintSynthesizer<F> for CompareCircuit<F> {
@@ -84,6 +142,10 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for CompareCircuit<F> {
         for (shorter_var, larger_var) in shorter_vars.iter().zip(larger_vars.iter()) {
             larger_var.enforce_equal(shorter_var)?;
         }
+
+        // Public, and constrained so it's part of what the proof attests
+        let binding_var = FpVar::new_input(cs.clone(), || Ok(self.binding))?;
+        let _ = binding_var.square()?;
         Ok(())
     }
 }
@@ -121,7 +183,7 @@ mod tests {
 
         let (pk, vk) = setup_groth16(rng, circuit.clone()).expect("setup failed");
         let proof = create_proof(&pk, circuit, rng).expect("proof not generated");
-        let verified = verify_proof(&vk, small, &proof).expect("verification failed");
+        let verified = verify_proof(&vk, small, &[], &proof).expect("verification failed");
 
         assert!(verified, "this can't be verified");
     }
@@ -139,6 +201,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_new_from_geohash() {
+        assert!(CompareCircuit::new_from_geohash("bcd", "bcdefg").is_some());
+        assert!(CompareCircuit::new_from_geohash("", "bcdefg").is_none());
+        assert!(CompareCircuit::new_from_geohash("bcd", "bcfefg").is_none());
+        assert!(CompareCircuit::new_from_geohash("abc", "abcdef").is_none());
+    }
+
+    #[test]
+    fn test_bound_to() {
+        let rng = &mut thread_rng();
+        let circuit = CompareCircuit::new_from_str("bcd", "bcdefg");
+        let (pk, vk) = setup_groth16(rng, circuit.clone()).expect("setup failed");
+        let proof =
+            create_proof(&pk, circuit.bound_to(b"alice"), rng).expect("proof not generated");
+
+        assert!(verify_proof(&vk, "bcd", b"alice", &proof).expect("verification failed"));
+        assert!(!verify_proof(&vk, "bcd", b"bob", &proof).expect("verification failed"));
+
+        // A re-randomised proof is still bound to the same input
+        let proof = Groth16::<Bn254>::rerandomize_proof(&vk, &proof, rng);
+        assert!(verify_proof(&vk, "bcd", b"alice", &proof).expect("verification failed"));
+        assert!(!verify_proof(&vk, "bcd", b"bob", &proof).expect("verification failed"));
+    }
+
+    #[test]
+    fn test_dev_setup() {
+        let rng = &mut thread_rng();
+        let (pk, vk) = dev_setup(3).expect("setup failed");
+        assert_eq!(dev_setup(3).expect("setup failed").1, vk);
+
+        let circuit = CompareCircuit::new_from_str("bcd", "bcdefg").bound_to(b"alice");
+        let proof = create_proof(&pk, circuit, rng).expect("proof not generated");
+        assert!(verify_proof(&vk, "bcd", b"alice", &proof).expect("verification failed"));
+    }
+
     #[test]
     fn test_empty_shorter() {
         let result = std::panic::catch_unwind(|| {
@@ -0,0 +1,554 @@
+//! Geohash helpers shared by the attendance pallet, the oracle and the prover.
+//!
+//! A geohash interleaves longitude and latitude bits, five to a base32 character, so each extra
+//! character narrows the cell it describes. Everything here works without `std`, so the same
+//! rules apply on chain and off chain.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+/// The characters a geohash is written in, indexed by the five bits they encode.
+pub const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// The longest geohash a [`Cell`] can index, 30 bits per axis.
+pub const MAX_CELL_PRECISION: usize = 12;
+
+/// The distance between the poles along a meridian, in metres.
+pub const MERIDIAN_METRES: u64 = 20_003_931;
+
+/// The circumference of the equator, in metres.
+pub const EQUATOR_METRES: u64 = 40_075_017;
+
+/// The area covered by a geohash, in degrees.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+}
+
+impl BoundingBox {
+    /// The latitude and longitude at the centre of the box.
+    pub fn center(&self) -> (f64, f64) {
+        (
+            (self.min_latitude + self.max_latitude) / 2.0,
+            (self.min_longitude + self.max_longitude) / 2.0,
+        )
+    }
+
+    /// The height of the box in degrees of latitude.
+    pub fn height(&self) -> f64 {
+        self.max_latitude - self.min_latitude
+    }
+
+    /// The width of the box in degrees of longitude.
+    pub fn width(&self) -> f64 {
+        self.max_longitude - self.min_longitude
+    }
+}
+
+/// The direction of a neighbouring cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    // The number of rows north and columns east moved
+    fn offset(self) -> (i64, i64) {
+        match self {
+            Direction::North => (1, 0),
+            Direction::NorthEast => (1, 1),
+            Direction::East => (0, 1),
+            Direction::SouthEast => (-1, 1),
+            Direction::South => (-1, 0),
+            Direction::SouthWest => (-1, -1),
+            Direction::West => (0, -1),
+            Direction::NorthWest => (1, -1),
+        }
+    }
+}
+
+/// The position of a geohash cell in the grid of cells of the same precision.
+///
+/// Only integer arithmetic is used, so cells can be compared deterministically on chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    /// The row of the cell, counted from the south pole
+    pub row: u64,
+    /// The column of the cell, counted east from the antimeridian
+    pub column: u64,
+    /// The number of latitude bits in the geohash
+    pub row_bits: u32,
+    /// The number of longitude bits in the geohash
+    pub column_bits: u32,
+}
+
+impl Cell {
+    /// The cell of `geohash`, or `None` if it isn't valid or longer than [`MAX_CELL_PRECISION`].
+    pub fn from_geohash(geohash: &[u8]) -> Option<Self> {
+        if !is_valid(geohash) || geohash.len() > MAX_CELL_PRECISION {
+            return None;
+        }
+
+        let mut cell = Cell {
+            row: 0,
+            column: 0,
+            row_bits: 0,
+            column_bits: 0,
+        };
+        let mut even = true;
+        for c in geohash {
+            let index = ALPHABET.iter().position(|a| a == c)? as u64;
+            for bit in (0..5).rev() {
+                let value = (index >> bit) & 1;
+                if even {
+                    cell.column = (cell.column << 1) | value;
+                    cell.column_bits += 1;
+                } else {
+                    cell.row = (cell.row << 1) | value;
+                    cell.row_bits += 1;
+                }
+                even = !even;
+            }
+        }
+        Some(cell)
+    }
+
+    /// The geohash of the cell.
+    pub fn to_geohash(&self) -> Vec<u8> {
+        let length = (self.row_bits + self.column_bits) / 5;
+        let (mut row_bits, mut column_bits) = (self.row_bits, self.column_bits);
+        let mut even = true;
+        (0..length)
+            .map(|_| {
+                let mut index = 0;
+                for _ in 0..5 {
+                    let value = if even {
+                        column_bits -= 1;
+                        (self.column >> column_bits) & 1
+                    } else {
+                        row_bits -= 1;
+                        (self.row >> row_bits) & 1
+                    };
+                    index = (index << 1) | value;
+                    even = !even;
+                }
+                ALPHABET[index as usize]
+            })
+            .collect()
+    }
+
+    /// The number of rows of cells with this precision.
+    pub fn rows(&self) -> u64 {
+        1 << self.row_bits
+    }
+
+    /// The number of columns of cells with this precision.
+    pub fn columns(&self) -> u64 {
+        1 << self.column_bits
+    }
+
+    /// The cell `rows` north and `columns` east of this one.
+    ///
+    /// Columns wrap around the antimeridian, but `None` is returned past either pole.
+    pub fn offset(&self, rows: i64, columns: i64) -> Option<Self> {
+        let row = (self.row as i64).checked_add(rows)?;
+        if !(0..self.rows() as i64).contains(&row) {
+            return None;
+        }
+        let column = (self.column as i64)
+            .checked_add(columns)?
+            .rem_euclid(self.columns() as i64);
+        Some(Cell {
+            row: row as u64,
+            column: column as u64,
+            ..*self
+        })
+    }
+
+    /// The number of rows and columns between two cells of the same precision, going the
+    /// shorter way around the antimeridian, or `None` if their precisions differ.
+    pub fn distance(&self, other: &Cell) -> Option<(u64, u64)> {
+        if (self.row_bits, self.column_bits) != (other.row_bits, other.column_bits) {
+            return None;
+        }
+        let columns = self.column.abs_diff(other.column);
+        Some((
+            self.row.abs_diff(other.row),
+            columns.min(self.columns() - columns),
+        ))
+    }
+}
+
+/// Whether `geohash` is non-empty and only uses characters from [`ALPHABET`].
+pub fn is_valid(geohash: &[u8]) -> bool {
+    !geohash.is_empty() && geohash.iter().all(|c| ALPHABET.contains(c))
+}
+
+/// The number of characters in `geohash`, or `None` if it isn't valid.
+pub fn precision(geohash: &[u8]) -> Option<usize> {
+    is_valid(geohash).then_some(geohash.len())
+}
+
+/// Whether the cell of `geohash` lies within the cell of `area`.
+pub fn contains(area: &[u8], geohash: &[u8]) -> bool {
+    is_valid(area) && is_valid(geohash) && geohash.starts_with(area)
+}
+
+/// Encodes a location as a geohash of `precision` characters.
+///
+/// Returns `None` if the precision is zero or the location is outside of the valid range.
+pub fn encode(latitude: f64, longitude: f64, precision: usize) -> Option<Vec<u8>> {
+    if precision == 0
+        || !(-90.0..=90.0).contains(&latitude)
+        || !(-180.0..=180.0).contains(&longitude)
+    {
+        return None;
+    }
+
+    let mut latitudes = (-90.0, 90.0);
+    let mut longitudes = (-180.0, 180.0);
+    let mut geohash = Vec::with_capacity(precision);
+    let mut index = 0;
+    // Bits alternate between longitude and latitude, starting with longitude
+    for bit in 0.. {
+        let (range, value) = if bit % 2 == 0 {
+            (&mut longitudes, longitude)
+        } else {
+            (&mut latitudes, latitude)
+        };
+        let mid = (range.0 + range.1) / 2.0;
+        index <<= 1;
+        if value >= mid {
+            index |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+
+        if bit % 5 == 4 {
+            geohash.push(ALPHABET[index]);
+            if geohash.len() == precision {
+                break;
+            }
+            index = 0;
+        }
+    }
+    Some(geohash)
+}
+
+/// Decodes `geohash` to the area it covers, or `None` if it isn't valid.
+pub fn decode(geohash: &[u8]) -> Option<BoundingBox> {
+    if !is_valid(geohash) {
+        return None;
+    }
+
+    let mut bbox = BoundingBox {
+        min_latitude: -90.0,
+        max_latitude: 90.0,
+        min_longitude: -180.0,
+        max_longitude: 180.0,
+    };
+    let mut even = true;
+    for c in geohash {
+        let index = ALPHABET.iter().position(|a| a == c)?;
+        for bit in (0..5).rev() {
+            let (min, max) = if even {
+                (&mut bbox.min_longitude, &mut bbox.max_longitude)
+            } else {
+                (&mut bbox.min_latitude, &mut bbox.max_latitude)
+            };
+            let mid = (*min + *max) / 2.0;
+            if (index >> bit) & 1 == 1 {
+                *min = mid;
+            } else {
+                *max = mid;
+            }
+            even = !even;
+        }
+    }
+    Some(bbox)
+}
+
+/// The cell of the same precision next to `geohash` in `direction`.
+///
+/// Longitude wraps around the antimeridian, but there is no cell north of the top row or south
+/// of the bottom row, so `None` is returned there or if `geohash` isn't a valid [`Cell`].
+pub fn neighbor(geohash: &[u8], direction: Direction) -> Option<Vec<u8>> {
+    let (rows, columns) = direction.offset();
+    Cell::from_geohash(geohash)?
+        .offset(rows, columns)
+        .map(|cell| cell.to_geohash())
+}
+
+/// The cells surrounding `geohash`, clockwise from north, skipping any beyond the poles.
+///
+/// Returns `None` if `geohash` isn't a valid [`Cell`].
+pub fn neighbors(geohash: &[u8]) -> Option<Vec<Vec<u8>>> {
+    Cell::from_geohash(geohash).map(|_| {
+        Direction::ALL
+            .iter()
+            .filter_map(|direction| neighbor(geohash, *direction))
+            .collect()
+    })
+}
+
+/// Whether `geohash` lies within `rows` rows and `columns` columns of the `center` cell.
+///
+/// Only the first `center.len()` characters of `geohash` locate it, so it must be at least as
+/// precise as `center`.
+pub fn within_cells(center: &[u8], geohash: &[u8], rows: u64, columns: u64) -> bool {
+    let (Some(center), true) = (Cell::from_geohash(center), is_valid(geohash)) else {
+        return false;
+    };
+    let length = ((center.row_bits + center.column_bits) / 5) as usize;
+    geohash
+        .get(..length)
+        .and_then(Cell::from_geohash)
+        .and_then(|cell| center.distance(&cell))
+        .is_some_and(|(r, c)| r <= rows && c <= columns)
+}
+
+/// Whether `geohash` lies in a cell whose nearest edge is less than `metres` from the edge of the
+/// `center` cell, or in the center cell itself.
+///
+/// The gap between the cells is measured as a straight line, with east-west distances scaled to
+/// the latitude of the center cell. Only the first `center.len()` characters of `geohash` locate
+/// it, so it must be at least as precise as `center`.
+pub fn within_distance(center: &[u8], geohash: &[u8], metres: u32) -> bool {
+    let (Some(center), true) = (Cell::from_geohash(center), is_valid(geohash)) else {
+        return false;
+    };
+    let length = ((center.row_bits + center.column_bits) / 5) as usize;
+    let Some((rows, columns)) = geohash
+        .get(..length)
+        .and_then(Cell::from_geohash)
+        .and_then(|cell| center.distance(&cell))
+    else {
+        return false;
+    };
+    if (rows, columns) == (0, 0) {
+        return true;
+    }
+
+    // The gaps between the nearest edges, in millimetres
+    let (numerator, denominator) = latitude_cosine(&center);
+    let north = u128::from(rows.saturating_sub(1)) * u128::from(MERIDIAN_METRES) * 1_000
+        / u128::from(center.rows());
+    let east =
+        u128::from(columns.saturating_sub(1)) * u128::from(EQUATOR_METRES) * 1_000 * numerator
+            / (u128::from(center.columns()) * denominator);
+    let limit = u128::from(metres) * 1_000;
+    north * north + east * east < limit * limit
+}
+
+// The cosine of the latitude at the center of `cell` as a fraction, with Bhaskara's
+// approximation, which is within 0.002 of the true value
+fn latitude_cosine(cell: &Cell) -> (u128, u128) {
+    // In thousandths of a degree, where 180 degrees is 180_000
+    let latitude = (u128::from(cell.row) * 2 + 1) * 90_000 / u128::from(cell.rows());
+    let latitude = latitude.abs_diff(90_000);
+    let half_turn = 180_000u128 * 180_000;
+    let square = latitude * latitude;
+    (half_turn - 4 * square, half_turn + square)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid() {
+        for geohash in ["bcd", "ezs42", "u4pruydqqvj", "0"] {
+            assert!(is_valid(geohash.as_bytes()), "{geohash} should be valid");
+        }
+        for geohash in ["", "abc", "!@#", "ABC", "bcd "] {
+            assert!(!is_valid(geohash.as_bytes()), "{geohash} should be invalid");
+        }
+        assert_eq!(precision(b"ezs42"), Some(5));
+        assert_eq!(precision(b""), None);
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(
+            encode(57.64911, 10.40744, 11).as_deref(),
+            Some(&b"u4pruydqqvj"[..])
+        );
+        assert_eq!(encode(42.605, -5.603, 5).as_deref(), Some(&b"ezs42"[..]));
+        assert_eq!(encode(0.0, 0.0, 0), None);
+        assert_eq!(encode(91.0, 0.0, 5), None);
+        assert_eq!(encode(0.0, -180.5, 5), None);
+        assert_eq!(encode(f64::NAN, 0.0, 5), None);
+    }
+
+    #[test]
+    fn test_decode() {
+        let bbox = decode(b"ezs42").expect("valid geohash");
+        let (latitude, longitude) = bbox.center();
+        assert!((latitude - 42.605).abs() < bbox.height());
+        assert!((longitude - -5.603).abs() < bbox.width());
+        assert_eq!(
+            encode(latitude, longitude, 5).as_deref(),
+            Some(&b"ezs42"[..])
+        );
+
+        // Each character halves both sides of the box at least once
+        let outer = decode(b"ezs").expect("valid geohash");
+        assert!(outer.min_latitude <= bbox.min_latitude && bbox.max_latitude <= outer.max_latitude);
+        assert!(outer.height() > bbox.height() && outer.width() > bbox.width());
+        assert_eq!(decode(b"ezsa"), None);
+        assert_eq!(decode(b""), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        assert!(contains(b"ezs", b"ezs42"));
+        assert!(contains(b"ezs42", b"ezs42"));
+        assert!(!contains(b"ezs42", b"ezs"));
+        assert!(!contains(b"", b"ezs42"));
+        assert!(!contains(b"ezs", b"ezsa"));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(
+            neighbor(b"gbsuv", Direction::North).as_deref(),
+            Some(&b"gbsvj"[..])
+        );
+        assert_eq!(
+            neighbor(b"gbsuv", Direction::East).as_deref(),
+            Some(&b"gbsuy"[..])
+        );
+        assert_eq!(
+            neighbor(b"gbsuv", Direction::South).as_deref(),
+            Some(&b"gbsut"[..])
+        );
+        assert_eq!(
+            neighbor(b"gbsuv", Direction::West).as_deref(),
+            Some(&b"gbsuu"[..])
+        );
+        for geohash in neighbors(b"gbsuv").expect("valid geohash") {
+            assert_eq!(geohash.len(), 5);
+            assert_ne!(geohash, b"gbsuv");
+        }
+        assert_eq!(neighbors(b"gbsuv").map(|cells| cells.len()), Some(8));
+
+        // Longitude wraps around, latitude stops at the poles
+        assert_eq!(neighbor(b"z", Direction::East).as_deref(), Some(&b"b"[..]));
+        assert_eq!(neighbor(b"z", Direction::North), None);
+        assert_eq!(neighbors(b"z").map(|cells| cells.len()), Some(5));
+        assert_eq!(neighbors(b"!"), None);
+    }
+
+    #[test]
+    fn test_cell() {
+        for geohash in ["0", "zzzzzzzzzzzz", "gbsuv", "u4pruydqqvj"] {
+            let cell = Cell::from_geohash(geohash.as_bytes()).expect("valid cell");
+            assert_eq!(cell.to_geohash(), geohash.as_bytes());
+        }
+        let cell = Cell::from_geohash(b"0").expect("valid cell");
+        assert_eq!(
+            (cell.row, cell.column, cell.rows(), cell.columns()),
+            (0, 0, 4, 8)
+        );
+        assert_eq!(Cell::from_geohash(b"zzzzzzzzzzzzz"), None);
+
+        // Neighbors agree with the bounding boxes of the cells
+        let center = decode(b"gbsuv").expect("valid geohash");
+        let north = decode(&neighbor(b"gbsuv", Direction::North).unwrap()).unwrap();
+        let east = decode(&neighbor(b"gbsuv", Direction::East).unwrap()).unwrap();
+        assert_eq!(north.min_latitude, center.max_latitude);
+        assert_eq!(east.min_longitude, center.max_longitude);
+    }
+
+    #[test]
+    fn test_within_cells() {
+        // Either side of a cell edge
+        let east = neighbor(b"gbsuv", Direction::East).unwrap();
+        let mut location = east.clone();
+        location.extend_from_slice(b"0");
+        assert!(!contains(b"gbsuv", &location));
+        assert!(within_cells(b"gbsuv", &location, 1, 1));
+        assert!(!within_cells(b"gbsuv", &location, 1, 0));
+
+        // Across the antimeridian
+        let west = encode(0.0, 179.99, 4).unwrap();
+        let east = encode(0.0, -179.99, 4).unwrap();
+        assert_eq!(neighbor(&west, Direction::East), Some(east.clone()));
+        assert!(within_cells(&west, &east, 0, 1));
+        assert!(within_cells(&east, &west, 0, 1));
+
+        // Locations must be at least as precise as the center
+        assert!(!within_cells(b"gbsuv", b"gbsu", 1, 1));
+        assert!(!within_cells(b"gbsuv", b"gbsuva", 1, 1));
+        assert!(!within_cells(b"", b"gbsuv", 1, 1));
+    }
+
+    #[test]
+    fn test_within_distance() {
+        // A cell at precision 6 is about 610m by 1220m at the equator
+        let center = encode(0.0, 0.0, 6).unwrap();
+        let two_north = neighbor(
+            &neighbor(&center, Direction::North).unwrap(),
+            Direction::North,
+        )
+        .unwrap();
+        let one_east = neighbor(&center, Direction::East).unwrap();
+
+        assert!(within_distance(&center, &center, 0));
+        assert!(!within_distance(&center, &one_east, 0));
+        assert!(within_distance(&center, &one_east, 1));
+        assert!(!within_distance(&center, &two_north, 1));
+        assert!(!within_distance(&center, &two_north, 600));
+        assert!(within_distance(&center, &two_north, 700));
+        assert!(!within_distance(b"", &center, 700));
+
+        // Diagonal gaps are measured in a straight line, about 1370m here
+        let two_north_east = neighbor(
+            &neighbor(&two_north, Direction::East).unwrap(),
+            Direction::East,
+        )
+        .unwrap();
+        assert!(!within_distance(&center, &two_north_east, 1_300));
+        assert!(within_distance(&center, &two_north_east, 1_400));
+    }
+
+    #[test]
+    fn test_within_distance_at_latitude() {
+        // At 60 degrees a cell at precision 6 is only about 610m wide
+        let center = encode(60.0, 0.0, 6).unwrap();
+        let two_east = neighbor(
+            &neighbor(&center, Direction::East).unwrap(),
+            Direction::East,
+        )
+        .unwrap();
+
+        assert!(!within_distance(&center, &two_east, 600));
+        assert!(within_distance(&center, &two_east, 650));
+    }
+}
@@ -3,8 +3,8 @@
 //! This module provides an implementation of the `Hasher` trait
 //! using the Blake2-256 cryptographic hash function.
 
+use oracle::{Hash, HashAlgorithm, Hasher};
 use sp_io::hashing::blake2_256;
-use oracle::{Hash, Hasher};
 
 /// Implementation of the `Hasher` trait using Blake2-256.
 ///
@@ -13,6 +13,9 @@ use oracle::{Hash, Hasher};
 pub struct Blake2_256;
 
 impl Hasher for Blake2_256 {
+    /// Attestations hashed with this hasher are marked as Blake2-256.
+    const ALGORITHM: HashAlgorithm = HashAlgorithm::Blake2_256;
+
     /// Computes a Blake2-256 hash of the provided message.
     ///
     /// # Arguments
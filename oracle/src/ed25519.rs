@@ -3,8 +3,8 @@
 //! This module provides an implementation of the `Signer` trait
 //! using the Ed25519 elliptic curve digital signature algorithm.
 
-use ed25519_dalek::{Signer, SigningKey};
-use oracle::{Hash, Key, SignerError};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use oracle::{Hash, Key, SignatureAlgorithm, SignerError};
 use rand::rngs::OsRng;
 
 /// Implementation of the `Signer` trait using the Ed25519 signature algorithm.
@@ -20,6 +20,9 @@ impl oracle::Signer for Ed25519 {
     /// Ed25519 signatures are binary data represented as a byte vector.
     type Signature = Vec<u8>;
 
+    /// Attestations signed with this signer are marked as Ed25519.
+    const ALGORITHM: SignatureAlgorithm = SignatureAlgorithm::Ed25519;
+
     /// Signs a message hash using an Ed25519 private key.
     ///
     /// # Arguments
@@ -47,6 +50,45 @@ impl oracle::Signer for Ed25519 {
         Ok(signature.to_vec())
     }
 
+    /// Derives the Ed25519 public key (verifying key) for a private key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The private key (signing key)
+    ///
+    /// # Returns
+    ///
+    /// The public key used to verify signatures made with `key`
+    fn public_key(key: Key) -> Key {
+        Key::new(
+            SigningKey::from_bytes(key.as_bytes())
+                .verifying_key()
+                .to_bytes(),
+        )
+    }
+
+    /// Verifies an Ed25519 signature over a message hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The hash of the message that was signed
+    /// * `signature` - The 64-byte Ed25519 signature
+    /// * `public_key` - The public key (verifying key) of the signer
+    ///
+    /// # Returns
+    ///
+    /// `true` if the signature is valid, `false` if it isn't or either the signature
+    /// or the public key is malformed.
+    fn verify(message: Hash, signature: &[u8], public_key: Key) -> bool {
+        let Ok(verifying_key) = VerifyingKey::from_bytes(public_key.as_bytes()) else {
+            return false;
+        };
+        let Ok(signature) = Signature::from_slice(signature) else {
+            return false;
+        };
+        verifying_key.verify(message.as_bytes(), &signature).is_ok()
+    }
+
     /// Generates a new Ed25519 key pair for signing and verification.
     ///
     /// This function generates a cryptographically secure random Ed25519 key pair
@@ -74,3 +116,44 @@ impl oracle::Signer for Ed25519 {
         )
     }
 }
+
+#[test]
+fn test_sign_and_verify() {
+    use oracle::Signer as _;
+
+    let (key, public_key) = Ed25519::generate_key();
+    let message = Hash::new([7; 32]);
+    let signature = Ed25519::sign(message, key).unwrap();
+    assert!(Ed25519::verify(message, &signature, public_key));
+
+    // Another message, key or a malformed signature is rejected
+    assert!(!Ed25519::verify(Hash::new([8; 32]), &signature, public_key));
+    let (_, other) = Ed25519::generate_key();
+    assert!(!Ed25519::verify(message, &signature, other));
+    assert!(!Ed25519::verify(message, &signature[..63], public_key));
+}
+
+#[test]
+fn test_verify_attestation() {
+    use crate::blake2_256::Blake2_256;
+    use oracle::{sign_attestation, verify_attestation, AttestationPayload};
+
+    let (key, public_key) = Ed25519::generate_key();
+    let mut payload = AttestationPayload {
+        account: [1; 32],
+        challenge: 5,
+        location: b"gcpuvxr1".to_vec(),
+        expires_at: 10,
+        nonce: 2,
+        genesis_hash: [3; 32],
+    };
+    let signature = sign_attestation::<Ed25519, Blake2_256>(key, &payload).unwrap();
+    assert!(verify_attestation::<Ed25519, Blake2_256>(
+        public_key, &payload, &signature
+    ));
+
+    payload.location = b"gcpuvxr2".to_vec();
+    assert!(!verify_attestation::<Ed25519, Blake2_256>(
+        public_key, &payload, &signature
+    ));
+}
@@ -1,4 +1,6 @@
-use serde::ser::Serialize;
+use codec::{Decode, DecodeAll, Encode};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 
 /// A 32-byte cryptographic key used for operations like signing.
 ///
@@ -56,6 +58,258 @@ impl Hash {
     }
 }
 
+/// The payload signed by the oracle to attest a location for an account.
+///
+/// This mirrors `pallet_attendance::AttestationPayload` for a runtime using 32-byte
+/// account ids and `u32` block numbers. It is SCALE encoded before hashing so the
+/// signature is bound to a single account, challenge and chain.
+#[derive(Serialize, Deserialize, Encode, Decode, Clone, Debug, PartialEq)]
+pub struct AttestationPayload {
+    /// The account the attestation is issued to
+    #[serde(with = "hex::serde")]
+    pub account: [u8; 32],
+    /// The identifier of the challenge being attended
+    pub challenge: u32,
+    /// The location geohash obtained by the oracle
+    #[serde(with = "geohash_string")]
+    pub location: Vec<u8>,
+    /// The last block in which the attestation can be submitted
+    pub expires_at: u32,
+    /// A value unique to this attestation for the account
+    pub nonce: u64,
+    /// The genesis hash of the target chain
+    #[serde(with = "hex::serde")]
+    pub genesis_hash: [u8; 32],
+}
+
+/// Serializes a geohash as a string rather than an array of bytes.
+mod geohash_string {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(geohash: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&String::from_utf8_lossy(geohash))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let geohash = String::deserialize(deserializer)?;
+        if !geohash_utils::is_valid(geohash.as_bytes()) {
+            return Err(D::Error::custom(format!("invalid geohash: {geohash}")));
+        }
+        Ok(geohash.into_bytes())
+    }
+}
+
+/// The hash functions an attestation payload can be hashed with before signing.
+#[derive(Serialize, Deserialize, Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// Blake2b with a 256-bit output, as used by `pallet_attendance`
+    #[serde(rename = "blake2-256")]
+    Blake2_256,
+}
+
+/// The signature schemes an attestation can be signed with.
+#[derive(Serialize, Deserialize, Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    /// Ed25519, as used by `pallet_attendance`
+    #[serde(rename = "ed25519")]
+    Ed25519,
+}
+
+/// A signed attestation which describes itself, as output by `oracle run`.
+///
+/// Besides the signed payload it records how the location was obtained and which
+/// algorithms were used, so it can be checked without knowing how the oracle is set up.
+/// It can be written as JSON, SCALE or CBOR, see [`Encoding`].
+#[derive(Serialize, Deserialize, Encode, Decode, Clone, Debug, PartialEq)]
+pub struct Attestation {
+    /// The payload which was signed
+    pub payload: AttestationPayload,
+    /// The number of geohash characters the location was requested with
+    pub precision: u8,
+    /// Unix time in milliseconds at which the location was obtained
+    pub timestamp: u64,
+    /// The hash function the encoded payload was hashed with
+    pub hash_algorithm: HashAlgorithm,
+    /// The signature scheme of `signer` and `signature`
+    pub signature_algorithm: SignatureAlgorithm,
+    /// The public key of the oracle which signed the payload
+    #[serde(with = "hex::serde")]
+    pub signer: [u8; 32],
+    /// The signature over the hash of the encoded payload
+    #[serde(with = "hex::serde")]
+    pub signature: Vec<u8>,
+}
+
+/// The encodings an [`Attestation`] can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// JSON, with byte fields as hex strings
+    Json,
+    /// SCALE, the encoding used on chain
+    Scale,
+    /// CBOR, with byte fields as hex strings
+    Cbor,
+}
+
+impl Attestation {
+    /// Writes the attestation in `encoding`.
+    ///
+    /// # Returns
+    /// * `Result<Vec<u8>, EncodingError>` - The encoded attestation if successful, or an error
+    ///   if it couldn't be serialized
+    pub fn encode_as(&self, encoding: Encoding) -> Result<Vec<u8>, EncodingError> {
+        match encoding {
+            Encoding::Json => {
+                serde_json::to_vec(self).map_err(|e| EncodingError::Encode(e.to_string()))
+            }
+            Encoding::Scale => Ok(self.encode()),
+            Encoding::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(self, &mut bytes)
+                    .map_err(|e| EncodingError::Encode(e.to_string()))?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Reads an attestation written in `encoding`.
+    ///
+    /// # Returns
+    /// * `Result<Attestation, EncodingError>` - The attestation if successful, or an error if
+    ///   `bytes` aren't a whole attestation in `encoding`
+    pub fn decode_from(bytes: &[u8], encoding: Encoding) -> Result<Self, EncodingError> {
+        match encoding {
+            Encoding::Json => {
+                serde_json::from_slice(bytes).map_err(|e| EncodingError::Decode(e.to_string()))
+            }
+            Encoding::Scale => {
+                Self::decode_all(&mut &bytes[..]).map_err(|e| EncodingError::Decode(e.to_string()))
+            }
+            Encoding::Cbor => {
+                ciborium::from_reader(bytes).map_err(|e| EncodingError::Decode(e.to_string()))
+            }
+        }
+    }
+
+    /// The oracle's entry in the `attestations` of `submission_with_signature`.
+    ///
+    /// # Returns
+    /// The public key and signature, which SCALE encode as the pallet's bounded byte vectors
+    pub fn call_attestation(&self) -> (Vec<u8>, Vec<u8>) {
+        (self.signer.to_vec(), self.signature.clone())
+    }
+}
+
+impl From<Attestation> for PartialAttestation {
+    fn from(attestation: Attestation) -> Self {
+        Self {
+            payload: attestation.payload.encode(),
+            signature: OracleSignature {
+                oracle: attestation.signer,
+                signature: attestation.signature,
+            },
+        }
+    }
+}
+
+/// A single oracle's signature over an encoded attestation payload.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OracleSignature {
+    /// The public key of the oracle which produced the signature
+    #[serde(with = "hex::serde")]
+    pub oracle: [u8; 32],
+    /// The signature over the hash of the encoded payload
+    #[serde(with = "hex::serde")]
+    pub signature: Vec<u8>,
+}
+
+/// An attestation signed by one oracle, to be merged with others into an [`AttestationBundle`].
+///
+/// Challenges requiring more than one oracle only accept submissions carrying signatures from
+/// several oracles over the same payload. Each oracle's [`Attestation`] is reduced to a partial
+/// attestation which is later combined with [`merge_attestations`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PartialAttestation {
+    /// The SCALE encoded [`AttestationPayload`]
+    #[serde(with = "hex::serde")]
+    pub payload: Vec<u8>,
+    /// The oracle's signature over the payload
+    #[serde(flatten)]
+    pub signature: OracleSignature,
+}
+
+/// Signatures from distinct oracles over the same attestation payload.
+///
+/// The `attestations` are submitted together with the payload fields in
+/// `submission_with_signature`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AttestationBundle {
+    /// The SCALE encoded [`AttestationPayload`] every oracle signed
+    #[serde(with = "hex::serde")]
+    pub payload: Vec<u8>,
+    /// One signature per oracle
+    pub attestations: Vec<OracleSignature>,
+}
+
+/// Errors that can occur when merging partial attestations.
+#[derive(Error, Debug, PartialEq)]
+pub enum MergeError {
+    /// No partial attestations were provided.
+    #[error("no attestations to merge")]
+    Empty,
+
+    /// The partial attestations were signed over different payloads.
+    ///
+    /// Signatures over different payloads can't be combined, as the chain verifies
+    /// every signature against the same message.
+    #[error("attestations are for different payloads")]
+    PayloadMismatch,
+}
+
+/// Errors that can occur when writing or reading an [`Attestation`].
+#[derive(Error, Debug, PartialEq)]
+pub enum EncodingError {
+    /// The attestation couldn't be serialized.
+    ///
+    /// # Fields
+    /// * String - A description of what went wrong during encoding
+    #[error("failed to encode attestation: {0}")]
+    Encode(String),
+
+    /// The input isn't a valid attestation.
+    ///
+    /// # Fields
+    /// * String - A description of what went wrong during decoding
+    #[error("failed to decode attestation: {0}")]
+    Decode(String),
+}
+
+/// Errors that can occur when covering a GeoJSON polygon with geohash cells.
+#[derive(Error, Debug, PartialEq)]
+pub enum CoverError {
+    /// The input isn't valid GeoJSON.
+    ///
+    /// # Fields
+    /// * String - A description of what is wrong with the input
+    #[error("invalid GeoJSON: {0}")]
+    Json(String),
+
+    /// The GeoJSON isn't a `Polygon` or `MultiPolygon`, or a `Feature` holding one.
+    #[error("expected a Polygon or MultiPolygon")]
+    Geometry,
+
+    /// The precisions are outside of 1-12 characters, or the minimum is above the maximum.
+    #[error("precisions must be between 1 and 12, the minimum no more than the maximum")]
+    Precision,
+
+    /// The polygon can't be covered with the given number of cells.
+    ///
+    /// # Fields
+    /// * usize - The maximum number of cells
+    #[error("the cover needs more than {0} cells")]
+    TooManyCells(usize),
+}
+
 /// Errors that can occur during location operations.
 ///
 /// This enum represents the various ways that acquiring or
@@ -68,7 +322,17 @@ pub enum LocationError {
     /// or when location data is unavailable.
     #[error("failed to locate")]
     Location,
-    
+
+    /// The location source couldn't be read or never reported a position.
+    ///
+    /// This happens when a GPS device, recording or daemon is unavailable,
+    /// sends malformed data or has no fix.
+    ///
+    /// # Fields
+    /// * String - A description of what went wrong with the source
+    #[error("failed to read location source: {0}")]
+    Source(String),
+
     /// Failed to generate the location output in the required format.
     ///
     /// This happens when the raw location data cannot be converted to
@@ -83,14 +347,15 @@ pub enum LocationError {
 /// Trait for obtaining geographical location data.
 ///
 /// Implementors of this trait can provide location data from various sources
-/// (GPS, IP geolocation, etc.) and in different formats.
+/// (GPS, IP geolocation, etc.) and in different formats. Each implementor holds
+/// the configuration of its source, such as the device or address to read from.
 #[async_trait::async_trait]
 pub trait Location {
     /// The type representing location data.
     ///
     /// Must be able to be represented as a byte slice for hashing and signing.
     type Output: AsRef<[u8]>;
-    
+
     /// Asynchronously obtains the current geographical location.
     ///
     /// # Arguments
@@ -101,7 +366,7 @@ pub trait Location {
     /// # Returns
     /// * `Result<Self::Output, LocationError>` - The location data if successful,
     ///   or an error if obtaining the location failed.
-    async fn current_location(accuracy: u8) -> Result<Self::Output, LocationError>;
+    async fn current_location(&self, accuracy: u8) -> Result<Self::Output, LocationError>;
 }
 
 /// Trait for cryptographic hashing functionality.
@@ -109,6 +374,9 @@ pub trait Location {
 /// Implementors of this trait provide methods to hash arbitrary data
 /// into fixed-size Hash objects.
 pub trait Hasher {
+    /// The algorithm recorded in attestations hashed with this hasher.
+    const ALGORITHM: HashAlgorithm;
+
     /// Computes a cryptographic hash of the provided message.
     ///
     /// # Arguments
@@ -150,7 +418,10 @@ pub trait Signer {
     ///
     /// Must be serializable for storage or transmission.
     type Signature: Serialize;
-    
+
+    /// The algorithm recorded in attestations signed with this signer.
+    const ALGORITHM: SignatureAlgorithm;
+
     /// Signs a message hash using the provided key.
     ///
     /// # Arguments
@@ -161,7 +432,27 @@ pub trait Signer {
     /// * `Result<Self::Signature, SignerError>` - The signature if successful,
     ///   or an error if signing failed.
     fn sign(message: Hash, key: Key) -> Result<Self::Signature, SignerError>;
-    
+
+    /// Derives the public key for a private key.
+    ///
+    /// # Arguments
+    /// * `key` - The private key
+    ///
+    /// # Returns
+    /// The public key other parties use to verify signatures made with `key`
+    fn public_key(key: Key) -> Key;
+
+    /// Verifies a signature over a message hash.
+    ///
+    /// # Arguments
+    /// * `message` - The hash of the message that was signed
+    /// * `signature` - The signature to check
+    /// * `public_key` - The public key of the signer
+    ///
+    /// # Returns
+    /// `true` if `signature` was made over `message` with the private key of `public_key`
+    fn verify(message: Hash, signature: &[u8], public_key: Key) -> bool;
+
     /// Generates a new cryptographic key pair.
     ///
     /// # Returns
@@ -177,45 +468,663 @@ pub trait Signer {
 /// * `L` - A type that implements the Location trait
 ///
 /// # Arguments
+/// * `provider` - The source to obtain the location from
 /// * `accuracy` - The desired accuracy level for the location data
 ///
 /// # Returns
 /// * `Result<L::Output, LocationError>` - The location data if successful,
 ///   or an error if obtaining the location failed
-pub async fn location<L>(accuracy: u8) -> Result<L::Output, LocationError>
+pub async fn location<L>(provider: &L, accuracy: u8) -> Result<L::Output, LocationError>
 where
-    L: Location,
+    L: Location + ?Sized,
 {
-    L::current_location(accuracy).await
+    provider.current_location(accuracy).await
 }
 
-/// Signs location data using specified cryptographic components.
+/// Signs an attestation payload using specified cryptographic components.
 ///
 /// This function composes the hashing and signing operations:
-/// 1. Converts the location data to bytes
-/// 2. Hashes the bytes using the specified Hasher
+/// 1. SCALE encodes the payload
+/// 2. Hashes the encoded bytes using the specified Hasher
 /// 3. Signs the hash using the specified Signer and key
 ///
 /// # Type Parameters
-/// * `L` - A type that implements the Location trait
 /// * `S` - A type that implements the Signer trait
 /// * `H` - A type that implements the Hasher trait
 ///
 /// # Arguments
 /// * `key` - The private key to use for signing
-/// * `location` - The location data to sign
+/// * `payload` - The attestation payload to sign
 ///
 /// # Returns
 /// * `Result<S::Signature, SignerError>` - The signature if successful,
 ///   or an error if signing failed
-pub async fn sign_location<L, S, H>(
+pub fn sign_attestation<S, H>(
     key: Key,
-    location: L::Output,
+    payload: &AttestationPayload,
 ) -> Result<S::Signature, SignerError>
 where
-    L: Location,
     S: Signer,
     H: Hasher,
 {
-    S::sign(H::hash(location.as_ref()), key)
+    S::sign(H::hash(payload.encode()), key)
+}
+
+/// Signs an attestation payload and describes the result as an [`Attestation`].
+///
+/// # Type Parameters
+/// * `S` - A type that implements the Signer trait
+/// * `H` - A type that implements the Hasher trait
+///
+/// # Arguments
+/// * `key` - The private key to use for signing
+/// * `payload` - The attestation payload to sign
+/// * `precision` - The number of geohash characters the location was requested with
+/// * `timestamp` - Unix time in milliseconds at which the location was obtained
+///
+/// # Returns
+/// * `Result<Attestation, SignerError>` - The attestation if successful, or an error if
+///   signing failed
+pub fn attest<S, H>(
+    key: Key,
+    payload: AttestationPayload,
+    precision: u8,
+    timestamp: u64,
+) -> Result<Attestation, SignerError>
+where
+    S: Signer,
+    S::Signature: Into<Vec<u8>>,
+    H: Hasher,
+{
+    let signature = sign_attestation::<S, H>(key, &payload)?;
+    Ok(Attestation {
+        payload,
+        precision,
+        timestamp,
+        hash_algorithm: H::ALGORITHM,
+        signature_algorithm: S::ALGORITHM,
+        signer: *S::public_key(key).as_bytes(),
+        signature: signature.into(),
+    })
+}
+
+/// Verifies an oracle's signature over an attestation payload.
+///
+/// The payload is SCALE encoded and hashed exactly as `sign_attestation` and
+/// `pallet_attendance` do, so a signature rejected here is rejected by the chain.
+///
+/// # Type Parameters
+/// * `S` - A type that implements the Signer trait
+/// * `H` - A type that implements the Hasher trait
+///
+/// # Arguments
+/// * `public_key` - The public key of the oracle
+/// * `payload` - The attestation payload which was signed
+/// * `signature` - The oracle's signature
+///
+/// # Returns
+/// `true` if the signature is valid for the payload and public key
+pub fn verify_attestation<S, H>(
+    public_key: Key,
+    payload: &AttestationPayload,
+    signature: &[u8],
+) -> bool
+where
+    S: Signer,
+    H: Hasher,
+{
+    S::verify(H::hash(payload.encode()), signature, public_key)
+}
+
+/// Merges partial attestations from several oracles into a single bundle.
+///
+/// Partial attestations from the same oracle are only included once, so merging
+/// the same file twice doesn't count towards a challenge's oracle threshold.
+///
+/// # Arguments
+/// * `partials` - The partial attestations to merge
+///
+/// # Returns
+/// * `Result<AttestationBundle, MergeError>` - The merged bundle if successful, or an error if:
+///   - No partial attestations were provided
+///   - The partial attestations don't share the same payload
+pub fn merge_attestations<I>(partials: I) -> Result<AttestationBundle, MergeError>
+where
+    I: IntoIterator<Item = PartialAttestation>,
+{
+    let mut partials = partials.into_iter();
+    let first = partials.next().ok_or(MergeError::Empty)?;
+    let mut bundle = AttestationBundle {
+        payload: first.payload,
+        attestations: vec![first.signature],
+    };
+    for partial in partials {
+        if partial.payload != bundle.payload {
+            return Err(MergeError::PayloadMismatch);
+        }
+        if !bundle
+            .attestations
+            .iter()
+            .any(|a| a.oracle == partial.signature.oracle)
+        {
+            bundle.attestations.push(partial.signature);
+        }
+    }
+    Ok(bundle)
+}
+
+/// A polygon as `(longitude, latitude)` rings, the first of which is the outer boundary and
+/// the rest holes in it, as in GeoJSON.
+pub type Polygon = Vec<Vec<(f64, f64)>>;
+
+/// Computes the fewest geohash cells of `min_precision` to `precision` characters covering a
+/// GeoJSON polygon.
+///
+/// The input may be a `Polygon` or `MultiPolygon` geometry, or a `Feature` holding one.
+/// See [`polygon_cover`] for the cells returned.
+///
+/// # Arguments
+/// * `geojson` - The GeoJSON text of the venue's outline
+/// * `min_precision` - The fewest characters of a cell (1-12)
+/// * `precision` - The most characters of a cell (1-12)
+/// * `max_cells` - The most cells the cover may have
+///
+/// # Returns
+/// * `Result<Vec<String>, CoverError>` - The sorted cells if successful, or an error if the
+///   input isn't a polygon, the precisions are out of range or there are too many cells
+pub fn geojson_cover(
+    geojson: &str,
+    min_precision: usize,
+    precision: usize,
+    max_cells: usize,
+) -> Result<Vec<String>, CoverError> {
+    let value: serde_json::Value =
+        serde_json::from_str(geojson).map_err(|e| CoverError::Json(e.to_string()))?;
+    polygon_cover(
+        &geojson_polygons(&value)?,
+        min_precision,
+        precision,
+        max_cells,
+    )
+}
+
+/// Computes the fewest geohash cells of `min_precision` to `precision` characters covering
+/// `polygons`.
+///
+/// A cell of `precision` characters is part of the cover if any of its interior lies inside one
+/// of the polygons, so cells which only touch an edge are left out. Wherever all 32 cells of a
+/// parent at least `min_precision` characters long are part of the cover, the parent replaces
+/// them. This is the smallest set of cells a challenge needs for every location inside the
+/// polygons to be accepted.
+///
+/// Polygons crossing the antimeridian must be split in two, as GeoJSON recommends.
+///
+/// # Returns
+/// * `Result<Vec<String>, CoverError>` - The sorted cells if successful, or an error if the
+///   precisions are out of range or the cover needs more than `max_cells` cells
+pub fn polygon_cover(
+    polygons: &[Polygon],
+    min_precision: usize,
+    precision: usize,
+    max_cells: usize,
+) -> Result<Vec<String>, CoverError> {
+    if !(1..=precision).contains(&min_precision) || precision > geohash_utils::MAX_CELL_PRECISION {
+        return Err(CoverError::Precision);
+    }
+    let mut cells = Vec::new();
+    let precisions = min_precision..=precision;
+    cover_cell(
+        polygons,
+        &mut Vec::new(),
+        &precisions,
+        max_cells,
+        &mut cells,
+    )?;
+    cells.sort();
+    Ok(cells)
+}
+
+// Add the cells below `prefix` which overlap `polygons` to `cells`, or return `true` without
+// adding them if every cell below `prefix` does and it can replace them
+fn cover_cell(
+    polygons: &[Polygon],
+    prefix: &mut Vec<u8>,
+    precisions: &RangeInclusive<usize>,
+    max_cells: usize,
+    cells: &mut Vec<String>,
+) -> Result<bool, CoverError> {
+    let mut whole = Vec::new();
+    for &character in geohash_utils::ALPHABET {
+        prefix.push(character);
+        let extent = geohash_utils::decode(prefix).map_or(Overlap::Outside, |cell| {
+            polygons
+                .iter()
+                .map(|polygon| overlap(polygon, &cell))
+                .max()
+                .unwrap_or(Overlap::Outside)
+        });
+        let covered = match extent {
+            Overlap::Outside => false,
+            _ if prefix.len() == *precisions.end() => true,
+            Overlap::Inside if precisions.contains(&prefix.len()) => true,
+            _ => cover_cell(polygons, prefix, precisions, max_cells, cells)?,
+        };
+        if covered {
+            whole.push(character);
+        }
+        prefix.pop();
+    }
+
+    if whole.len() == geohash_utils::ALPHABET.len() && precisions.contains(&prefix.len()) {
+        return Ok(true);
+    }
+    for character in whole {
+        prefix.push(character);
+        cells.push(String::from_utf8_lossy(prefix).into_owned());
+        prefix.pop();
+    }
+    if cells.len() > max_cells {
+        return Err(CoverError::TooManyCells(max_cells));
+    }
+    Ok(false)
+}
+
+// How much of a cell lies inside a polygon
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Overlap {
+    Outside,
+    Partial,
+    Inside,
+}
+
+// How much of the interior of `cell` overlaps `polygon`, partly if one of its edges crosses the
+// cell and wholly if the cell lies inside it
+fn overlap(polygon: &Polygon, cell: &geohash_utils::BoundingBox) -> Overlap {
+    let edges = polygon
+        .iter()
+        .flat_map(|ring| ring.iter().zip(ring.iter().cycle().skip(1)));
+    let mut inside = false;
+    let (latitude, longitude) = cell.center();
+    for (&a, &b) in edges {
+        if crosses(a, b, cell) {
+            return Overlap::Partial;
+        }
+        // Count the edges a ray east of the center crosses, an odd number is inside
+        if (a.1 > latitude) != (b.1 > latitude)
+            && longitude < a.0 + (latitude - a.1) / (b.1 - a.1) * (b.0 - a.0)
+        {
+            inside = !inside;
+        }
+    }
+    if inside {
+        Overlap::Inside
+    } else {
+        Overlap::Outside
+    }
+}
+
+// Whether the segment from `a` to `b` passes through the interior of `cell`, clipping it
+// against each side in turn
+fn crosses(a: (f64, f64), b: (f64, f64), cell: &geohash_utils::BoundingBox) -> bool {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let (mut enter, mut exit) = (0.0_f64, 1.0_f64);
+    for (p, q) in [
+        (-dx, a.0 - cell.min_longitude),
+        (dx, cell.max_longitude - a.0),
+        (-dy, a.1 - cell.min_latitude),
+        (dy, cell.max_latitude - a.1),
+    ] {
+        if p == 0.0 {
+            // Parallel to this side, so outside of the cell unless strictly within it
+            if q <= 0.0 {
+                return false;
+            }
+        } else if p < 0.0 {
+            enter = enter.max(q / p);
+        } else {
+            exit = exit.min(q / p);
+        }
+    }
+    enter < exit
+}
+
+// The polygons of a GeoJSON `Polygon`, `MultiPolygon` or `Feature` holding either
+fn geojson_polygons(value: &serde_json::Value) -> Result<Vec<Polygon>, CoverError> {
+    let coordinates = &value["coordinates"];
+    match value["type"].as_str() {
+        Some("Feature") => geojson_polygons(&value["geometry"]),
+        Some("Polygon") => Ok(vec![geojson_polygon(coordinates)?]),
+        Some("MultiPolygon") => coordinates
+            .as_array()
+            .ok_or_else(|| CoverError::Json("coordinates must be an array".into()))?
+            .iter()
+            .map(geojson_polygon)
+            .collect(),
+        _ => Err(CoverError::Geometry),
+    }
+}
+
+fn geojson_polygon(value: &serde_json::Value) -> Result<Polygon, CoverError> {
+    let invalid = || CoverError::Json("polygon coordinates must be rings of positions".into());
+    let rings = value
+        .as_array()
+        .filter(|rings| !rings.is_empty())
+        .ok_or_else(invalid)?;
+    rings
+        .iter()
+        .map(|ring| -> Result<Vec<(f64, f64)>, CoverError> {
+            ring.as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|position| match position.as_array().map(Vec::as_slice) {
+                    Some([longitude, latitude, ..]) => longitude
+                        .as_f64()
+                        .zip(latitude.as_f64())
+                        .ok_or_else(invalid),
+                    _ => Err(invalid()),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_attestation_payload_encoding() {
+    let payload = AttestationPayload {
+        account: [1; 32],
+        challenge: 5,
+        location: b"bcdefg".to_vec(),
+        expires_at: 10,
+        nonce: 2,
+        genesis_hash: [3; 32],
+    };
+    let mut expected = vec![1; 32];
+    expected.extend(5u32.to_le_bytes());
+    expected.extend([24, b'b', b'c', b'd', b'e', b'f', b'g']);
+    expected.extend(10u32.to_le_bytes());
+    expected.extend(2u64.to_le_bytes());
+    expected.extend([3; 32]);
+    assert_eq!(payload.encode(), expected);
+}
+
+#[cfg(test)]
+fn attestation() -> Attestation {
+    Attestation {
+        payload: AttestationPayload {
+            account: [1; 32],
+            challenge: 5,
+            location: b"gcpuvx".to_vec(),
+            expires_at: 10,
+            nonce: 2,
+            genesis_hash: [3; 32],
+        },
+        precision: 6,
+        timestamp: 1_760_000_000_000,
+        hash_algorithm: HashAlgorithm::Blake2_256,
+        signature_algorithm: SignatureAlgorithm::Ed25519,
+        signer: [4; 32],
+        signature: vec![5; 64],
+    }
+}
+
+#[test]
+fn test_attestation_encodings() {
+    let attestation = attestation();
+    for encoding in [Encoding::Json, Encoding::Scale, Encoding::Cbor] {
+        let bytes = attestation.encode_as(encoding).unwrap();
+        assert_eq!(
+            Attestation::decode_from(&bytes, encoding),
+            Ok(attestation.clone())
+        );
+        assert!(Attestation::decode_from(&bytes[1..], encoding).is_err());
+    }
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&attestation.encode_as(Encoding::Json).unwrap()).unwrap();
+    assert_eq!(json["payload"]["location"], "gcpuvx");
+    assert_eq!(json["payload"]["account"], "01".repeat(32));
+    assert_eq!(json["hash_algorithm"], "blake2-256");
+    assert_eq!(json["signature_algorithm"], "ed25519");
+    assert_eq!(json["signer"], "04".repeat(32));
+
+    // Trailing bytes aren't part of a SCALE attestation
+    let mut scale = attestation.encode_as(Encoding::Scale).unwrap();
+    scale.push(0);
+    assert!(Attestation::decode_from(&scale, Encoding::Scale).is_err());
+
+    // A location which isn't a geohash is rejected
+    let json = String::from_utf8(attestation.encode_as(Encoding::Json).unwrap()).unwrap();
+    let json = json.replace("gcpuvx", "gcpuva");
+    assert!(Attestation::decode_from(json.as_bytes(), Encoding::Json).is_err());
+}
+
+#[test]
+fn test_attestation_matches_pallet_encoding() {
+    let attestation = attestation();
+
+    // The SCALE attestation starts with the payload as `pallet_attendance` decodes it
+    let scale = attestation.encode_as(Encoding::Scale).unwrap();
+    let payload = attestation.payload.encode();
+    assert_eq!(&scale[..payload.len()], &payload[..]);
+    assert_eq!(
+        AttestationPayload::decode(&mut &payload[..]).unwrap(),
+        attestation.payload
+    );
+
+    // The call entry encodes as a `(BoundedVec<u8, 32>, BoundedVec<u8, 64>)` with compact lengths
+    let mut expected: Vec<u8> = vec![32 << 2];
+    expected.extend([4; 32]);
+    expected.extend([1, 1]);
+    expected.extend([5; 64]);
+    assert_eq!(attestation.call_attestation().encode(), expected);
+
+    let partial = PartialAttestation::from(attestation.clone());
+    assert_eq!(partial.payload, payload);
+    assert_eq!(partial.signature.oracle, attestation.signer);
+    assert_eq!(partial.signature.signature, attestation.signature);
+}
+
+#[test]
+fn test_merge_attestations() {
+    let partial = |oracle: u8, payload: &[u8]| PartialAttestation {
+        payload: payload.to_vec(),
+        signature: OracleSignature {
+            oracle: [oracle; 32],
+            signature: vec![oracle; 64],
+        },
+    };
+
+    let bundle = merge_attestations([
+        partial(1, b"payload"),
+        partial(2, b"payload"),
+        partial(1, b"payload"),
+    ])
+    .unwrap();
+    assert_eq!(bundle.payload, b"payload");
+    assert_eq!(
+        bundle.attestations,
+        vec![partial(1, b"").signature, partial(2, b"").signature]
+    );
+
+    assert_eq!(merge_attestations([]), Err(MergeError::Empty));
+    assert_eq!(
+        merge_attestations([partial(1, b"payload"), partial(2, b"other")]),
+        Err(MergeError::PayloadMismatch)
+    );
+}
+
+#[test]
+fn test_partial_attestation_json() {
+    let partial = PartialAttestation {
+        payload: vec![0xab],
+        signature: OracleSignature {
+            oracle: [1; 32],
+            signature: vec![2; 2],
+        },
+    };
+    let json = serde_json::to_string(&partial).unwrap();
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"payload":"ab","oracle":"{}","signature":"0202"}}"#,
+            "01".repeat(32)
+        )
+    );
+    assert_eq!(
+        serde_json::from_str::<PartialAttestation>(&json).unwrap(),
+        partial
+    );
+}
+
+#[cfg(test)]
+fn cell_polygon(geohash: &str, margin: f64) -> Polygon {
+    // The outline of `geohash` grown by `margin` of a cell on every side
+    let cell = geohash_utils::decode(geohash.as_bytes()).unwrap();
+    let (dx, dy) = (cell.width() * margin, cell.height() * margin);
+    let (west, east) = (cell.min_longitude - dx, cell.max_longitude + dx);
+    let (south, north) = (cell.min_latitude - dy, cell.max_latitude + dy);
+    vec![vec![
+        (west, south),
+        (east, south),
+        (east, north),
+        (west, north),
+        (west, south),
+    ]]
+}
+
+#[test]
+fn test_polygon_cover() {
+    // Inside a single cell
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", -0.1)], 5, 5, 64),
+        Ok(vec!["gcpuv".into()])
+    );
+    // The outline of a cell, whose neighbours only touch it
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", 0.0)], 5, 5, 64),
+        Ok(vec!["gcpuv".into()])
+    );
+    // Spilling into the cells around it
+    let mut around = geohash_utils::neighbors(b"gcpuv")
+        .unwrap()
+        .into_iter()
+        .map(|cell| String::from_utf8(cell).unwrap())
+        .collect::<Vec<_>>();
+    around.push("gcpuv".into());
+    around.sort();
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", 0.1)], 5, 5, 64),
+        Ok(around.clone())
+    );
+
+    // A hole covering the center cell leaves only the cells around it
+    let mut ring = cell_polygon("gcpuv", 0.9);
+    ring.extend(cell_polygon("gcpuv", 0.1));
+    around.retain(|cell| cell != "gcpuv");
+    assert_eq!(polygon_cover(&[ring], 5, 5, 64), Ok(around));
+
+    // A triangle misses the corner cells on the side of its hypotenuse
+    let cell = geohash_utils::decode(b"gcpuv").unwrap();
+    let (west, south) = (
+        cell.min_longitude + cell.width() * 0.1,
+        cell.min_latitude + cell.height() * 0.1,
+    );
+    let (east, north) = (west + cell.width() * 1.7, south + cell.height() * 1.7);
+    let triangle = vec![vec![
+        (west, south),
+        (east, south),
+        (west, north),
+        (west, south),
+    ]];
+    let cover = polygon_cover(&[triangle], 5, 5, 64).unwrap();
+    assert!(cover.contains(&"gcpuv".to_string()));
+    assert!(!cover.contains(
+        &String::from_utf8(
+            geohash_utils::neighbor(b"gcpuv", geohash_utils::Direction::NorthEast).unwrap()
+        )
+        .unwrap()
+    ));
+
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", 0.0)], 0, 5, 64),
+        Err(CoverError::Precision)
+    );
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", 0.0)], 6, 5, 64),
+        Err(CoverError::Precision)
+    );
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", 0.0)], 5, 13, 64),
+        Err(CoverError::Precision)
+    );
+}
+
+#[test]
+fn test_polygon_cover_merges_cells() {
+    // Every cell of the outline is merged back into it
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", 0.0)], 4, 7, 64),
+        Ok(vec!["gcpuv".into()])
+    );
+    // But not shorter than the minimum precision
+    let cells = polygon_cover(&[cell_polygon("gcpuv", 0.0)], 6, 7, 64).unwrap();
+    assert_eq!(cells.len(), 32);
+    assert!(cells
+        .iter()
+        .all(|cell| cell.len() == 6 && cell.starts_with("gcpuv")));
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", 0.0)], 6, 7, 31),
+        Err(CoverError::TooManyCells(31))
+    );
+
+    // Only the cells along the edges of a larger outline are at the full precision
+    let cells = polygon_cover(&[cell_polygon("gcpuv", 0.1)], 4, 6, 64).unwrap();
+    assert_eq!(cells.len(), 29);
+    assert!(cells.contains(&"gcpuv".to_string()));
+    assert!(cells
+        .iter()
+        .all(|cell| !cell.starts_with("gcpuv") || cell.len() == 5));
+    assert!(cells.iter().any(|cell| cell.len() == 6));
+    assert_eq!(
+        polygon_cover(&[cell_polygon("gcpuv", 0.1)], 4, 6, 28),
+        Err(CoverError::TooManyCells(28))
+    );
+}
+
+#[test]
+fn test_geojson_cover() {
+    let cell = geohash_utils::decode(b"gcpuv").unwrap();
+    let (latitude, longitude) = cell.center();
+    let polygon = format!(
+        r#"{{"type":"Polygon","coordinates":[[[{w},{s}],[{e},{s}],[{e},{n}],[{w},{n}],[{w},{s}]]]}}"#,
+        w = longitude - 0.001,
+        e = longitude + 0.001,
+        s = latitude - 0.001,
+        n = latitude + 0.001,
+    );
+    assert_eq!(geojson_cover(&polygon, 5, 5, 64), Ok(vec!["gcpuv".into()]));
+
+    let feature = format!(r#"{{"type":"Feature","properties":{{}},"geometry":{polygon}}}"#);
+    assert_eq!(geojson_cover(&feature, 5, 5, 64), Ok(vec!["gcpuv".into()]));
+
+    let multi = polygon
+        .replace(
+            r#""Polygon","coordinates":["#,
+            r#""MultiPolygon","coordinates":[["#,
+        )
+        .replace("]]]}", "]]]]}");
+    assert_eq!(geojson_cover(&multi, 5, 5, 64), Ok(vec!["gcpuv".into()]));
+
+    assert_eq!(
+        geojson_cover(r#"{"type":"Point","coordinates":[0,0]}"#, 5, 5, 64),
+        Err(CoverError::Geometry)
+    );
+    assert!(matches!(
+        geojson_cover("{", 5, 5, 64),
+        Err(CoverError::Json(_))
+    ));
+    assert!(matches!(
+        geojson_cover(r#"{"type":"Polygon","coordinates":[[[0]]]}"#, 5, 5, 64),
+        Err(CoverError::Json(_))
+    ));
 }
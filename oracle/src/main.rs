@@ -11,26 +11,61 @@
 //! oracle generate
 //! ```
 //!
+//! ## Derive a deterministic key pair for development chains
+//! ```
+//! oracle generate --seed=attendance-dev-oracle
+//! ```
+//!
 //! ## Run the oracle with a specific key and accuracy
 //! ```
-//! oracle run --key=<hex_key> --accuracy=6
+//! oracle run --key=<hex_key> --accuracy=6 --account=<hex_account> --challenge=<id> \
+//!     --expires-at=<block> --nonce=<nonce> --genesis-hash=<hex_hash>
 //! ```
 //!
 //! ## Run using an environment variable for the key
 //! ```
-//! ORACLE_KEY=<hex_key> oracle run --accuracy=8
+//! ORACLE_KEY=<hex_key> oracle run --accuracy=8 --account=<hex_account> ...
+//! ```
+//!
+//! ## Run with the location from a GPS receiver, gpsd or fixed coordinates
+//! ```
+//! oracle run --provider=nmea --device=/dev/ttyUSB0 --accuracy=8 ...
+//! oracle run --provider=gpsd --gpsd=127.0.0.1:2947 --accuracy=8 ...
+//! oracle run --provider=fixed --latitude=51.5085 --longitude=-0.1257 --accuracy=8 ...
+//! ```
+//!
+//! ## Verify an oracle's signature over an attestation
+//! ```
+//! oracle verify --public-key=<hex_key> --location=<geohash> --signature=<hex_signature> \
+//!     --account=<hex_account> --challenge=<id> --expires-at=<block> --nonce=<nonce> \
+//!     --genesis-hash=<hex_hash>
+//! ```
+//!
+//! ## Merge attestations from several oracles
+//! ```
+//! oracle merge first.json second.json
+//! ```
+//!
+//! ## Cover a venue's GeoJSON outline with geohash cells
+//! ```
+//! oracle cover venue.geojson --precision=7
 //! ```
 
 mod blake2_256;
 mod ed25519;
 mod env;
-mod geohash;
+mod providers;
 
 use blake2_256::Blake2_256;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use codec::Encode;
 use ed25519::Ed25519;
-use geohash::Geohash;
-use oracle::{location, sign_location, Key, Signer};
+use oracle::{
+    attest, geojson_cover, location, merge_attestations, verify_attestation, Attestation,
+    AttestationPayload, Encoding, Hasher, Key, Location, PartialAttestation, Signer,
+};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Command-line arguments for the Oracle application.
 ///
@@ -44,6 +79,40 @@ struct Args {
     command: Commands,
 }
 
+/// Encodings `run` can output an attestation in.
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// JSON, with byte fields as hex strings
+    Json,
+    /// SCALE, the encoding used on chain
+    Scale,
+    /// CBOR, with byte fields as hex strings
+    Cbor,
+}
+
+impl From<Format> for Encoding {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Json => Encoding::Json,
+            Format::Scale => Encoding::Scale,
+            Format::Cbor => Encoding::Cbor,
+        }
+    }
+}
+
+/// Sources `run` can read the current location from.
+#[derive(Clone, Copy, ValueEnum)]
+enum Provider {
+    /// IP geolocation, only accurate to the network operator
+    Ip,
+    /// NMEA 0183 sentences from a GPS receiver's serial device or a recording
+    Nmea,
+    /// The gpsd daemon's JSON protocol
+    Gpsd,
+    /// Fixed coordinates, for testing
+    Fixed,
+}
+
 /// Subcommands supported by the Oracle application.
 ///
 /// This enum defines the different operations that can be
@@ -54,15 +123,23 @@ enum Commands {
     ///
     /// This command generates a new Ed25519 key pair and
     /// outputs both the private and public keys in hexadecimal format.
-    Generate,
-    
+    Generate {
+        /// Derive the key from this seed instead of generating a random one.
+        ///
+        /// The private key is the Blake2-256 hash of the seed, so the same seed
+        /// always gives the same key. Only use this for development chains.
+        #[arg(long)]
+        seed: Option<String>,
+    },
+
     /// Run the oracle to generate a signed location.
     ///
     /// This command:
-    /// 1. Gets the current location
+    /// 1. Gets the current location from the selected provider
     /// 2. Converts it to a geohash with the specified accuracy
-    /// 3. Signs it with the provided key or environment variable
-    /// 4. Outputs the signed data as JSON
+    /// 3. Binds it to the account, challenge and chain in an attestation payload
+    /// 4. Signs the payload with the provided key or environment variable
+    /// 5. Outputs the attestation as JSON, SCALE or CBOR
     Run {
         /// Hexadecimal private key for signing (optional if ORACLE_KEY env var is set).
         ///
@@ -70,7 +147,7 @@ enum Commands {
         /// optionally prefixed with "0x".
         #[arg(default_value = "")]
         key: String,
-        
+
         /// Geohash accuracy (1-12), determines precision of location data.
         ///
         /// Higher values provide more precise location data.
@@ -78,8 +155,122 @@ enum Commands {
         /// - 5: City level (~2.4km precision)
         /// - 6: Neighborhood level (~0.61km precision)
         /// - 8: Street level (~38m precision)
-        #[arg(default_value = "6")]
+        #[arg(default_value = "6", value_parser = clap::value_parser!(u8).range(1..=12))]
         accuracy: u8,
+
+        /// Hexadecimal account id (32 bytes) the attestation is issued to.
+        #[arg(long)]
+        account: String,
+
+        /// Identifier of the challenge being attended.
+        #[arg(long)]
+        challenge: u32,
+
+        /// Last block number in which the attestation can be submitted.
+        #[arg(long)]
+        expires_at: u32,
+
+        /// Nonce for the attestation, must be unique for the account.
+        #[arg(long)]
+        nonce: u64,
+
+        /// Hexadecimal genesis hash (32 bytes) of the target chain.
+        #[arg(long)]
+        genesis_hash: String,
+
+        /// Encoding of the attestation, binary encodings are output as hex.
+        #[arg(long, value_enum, default_value = "json")]
+        format: Format,
+
+        /// Source of the current location.
+        #[arg(long, value_enum, default_value = "ip")]
+        provider: Provider,
+
+        /// Serial device or recording to read NMEA sentences from.
+        #[arg(long, required_if_eq("provider", "nmea"))]
+        device: Option<PathBuf>,
+
+        /// Address of gpsd's socket.
+        #[arg(long, default_value = providers::GPSD_ADDRESS)]
+        gpsd: String,
+
+        /// Latitude in decimal degrees for the fixed provider.
+        #[arg(long, allow_hyphen_values = true, required_if_eq("provider", "fixed"))]
+        latitude: Option<f64>,
+
+        /// Longitude in decimal degrees for the fixed provider.
+        #[arg(long, allow_hyphen_values = true, required_if_eq("provider", "fixed"))]
+        longitude: Option<f64>,
+    },
+
+    /// Verify an oracle's signature over an attestation payload.
+    ///
+    /// This command rebuilds the payload from the arguments, hashes it exactly
+    /// as `pallet_attendance` does and checks the Ed25519 signature, exiting
+    /// with a non-zero status if it is invalid.
+    Verify {
+        /// Hexadecimal public key (32 bytes) of the oracle.
+        #[arg(long)]
+        public_key: String,
+
+        /// The geohash the oracle attested.
+        #[arg(long)]
+        location: String,
+
+        /// Hexadecimal Ed25519 signature (64 bytes) of the oracle.
+        #[arg(long)]
+        signature: String,
+
+        /// Hexadecimal account id (32 bytes) the attestation was issued to.
+        #[arg(long)]
+        account: String,
+
+        /// Identifier of the challenge being attended.
+        #[arg(long)]
+        challenge: u32,
+
+        /// Last block number in which the attestation can be submitted.
+        #[arg(long)]
+        expires_at: u32,
+
+        /// Nonce of the attestation.
+        #[arg(long)]
+        nonce: u64,
+
+        /// Hexadecimal genesis hash (32 bytes) of the target chain.
+        #[arg(long)]
+        genesis_hash: String,
+    },
+
+    /// Merge attestations from several oracles into one bundle.
+    ///
+    /// Each file should contain the JSON output of `run` by a different oracle
+    /// for the same payload. Duplicate oracles are only included once.
+    Merge {
+        /// Files containing JSON attestations output by `run`.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+
+    /// Cover a GeoJSON polygon with geohash cells.
+    ///
+    /// Outputs the smallest set of cells between the given precisions which
+    /// covers the polygon as a JSON array, to use as the cells of a challenge.
+    Cover {
+        /// File containing a GeoJSON Polygon or MultiPolygon, or a Feature holding one.
+        file: PathBuf,
+
+        /// Most characters of a cell (1-12), used along the outline.
+        #[arg(long, default_value = "7")]
+        precision: usize,
+
+        /// Fewest characters of a cell (1-12), the chain's minimum challenge precision.
+        #[arg(long, default_value = "4")]
+        min_precision: usize,
+
+        /// Most cells the cover may have, the chain's maximum challenge cells.
+        #[arg(long, default_value = "64")]
+        max_cells: usize,
     },
 }
 
@@ -87,23 +278,43 @@ enum Commands {
 ///
 /// This function:
 /// 1. Parses command-line arguments
-/// 2. Executes the requested command (Generate or Run)
+/// 2. Executes the requested command (Generate, Run, Verify, Merge or Cover)
 /// 3. Handles errors and outputs results
 #[tokio::main]
 async fn main() {
     let args = Args::parse();
 
     match args.command {
-        Commands::Generate => {
-            // Generate a new Ed25519 key pair
-            let (secret_key, public_key) = Ed25519::generate_key();
+        Commands::Generate { seed } => {
+            // Generate a new Ed25519 key pair, or derive one from the seed
+            let (secret_key, public_key) = match seed {
+                Some(seed) => {
+                    let secret_key = Key::new(*Blake2_256::hash(seed).as_bytes());
+                    (secret_key, Ed25519::public_key(secret_key))
+                }
+                None => Ed25519::generate_key(),
+            };
             println!(
                 "Private=0x{}\nPublic=0x{}",
                 env::array_to_hex(secret_key.as_bytes()),
                 env::array_to_hex(public_key.as_bytes()),
             );
         }
-        Commands::Run { key, accuracy } => {
+        Commands::Run {
+            key,
+            accuracy,
+            account,
+            challenge,
+            expires_at,
+            nonce,
+            genesis_hash,
+            format,
+            provider,
+            device,
+            gpsd,
+            latitude,
+            longitude,
+        } => {
             // Attempt to get the key from environment variable first, then from command line
             let key_result =
                 env::try_key_from_environment().or_else(|_| env::try_hex_to_array(key));
@@ -117,29 +328,200 @@ async fn main() {
                 }
             };
 
-            // Get the current location as a geohash
-            let location = match location::<Geohash>(accuracy).await {
+            let account = match env::try_hex_to_array(account) {
+                Ok(account) => account,
+                Err(e) => {
+                    eprintln!("Error: Failed to parse account: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let genesis_hash = match env::try_hex_to_array(genesis_hash) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    eprintln!("Error: Failed to parse genesis hash: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            // Get the current location as a geohash from the selected provider
+            let provider: Box<dyn Location<Output = String>> = match provider {
+                Provider::Ip => Box::new(providers::IpInfo),
+                Provider::Nmea => Box::new(providers::Nmea::new(device.unwrap_or_default())),
+                Provider::Gpsd => Box::new(providers::Gpsd::new(gpsd)),
+                Provider::Fixed => Box::new(providers::Fixed::new(
+                    latitude.unwrap_or_default(),
+                    longitude.unwrap_or_default(),
+                )),
+            };
+            let location = match location(provider.as_ref(), accuracy).await {
                 Ok(loc) => loc,
                 Err(e) => {
                     eprintln!("Error: Failed to get location: {}", e);
                     std::process::exit(1);
                 }
             };
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_millis() as u64)
+                .unwrap_or_default();
+
+            let payload = AttestationPayload {
+                account,
+                challenge,
+                location: location.into_bytes(),
+                expires_at,
+                nonce,
+                genesis_hash,
+            };
 
-            // Sign the location data
-            let signed_location = match sign_location::<Geohash, Ed25519, Blake2_256>(key, location).await {
-                Ok(sig) => sig,
+            // Sign the attestation payload
+            let attestation = match attest::<Ed25519, Blake2_256>(key, payload, accuracy, timestamp)
+            {
+                Ok(attestation) => attestation,
                 Err(e) => {
                     eprintln!("Error: Failed to sign location: {}", e);
                     std::process::exit(1);
                 }
             };
 
-            // Output the signed location as JSON
-            match serde_json::to_string(&signed_location) {
+            // Output the attestation as JSON, or hex for the binary encodings
+            match attestation.encode_as(format.into()) {
+                Ok(bytes) => match format {
+                    Format::Json => println!("{}", String::from_utf8_lossy(&bytes)),
+                    Format::Scale | Format::Cbor => println!("0x{}", env::array_to_hex(bytes)),
+                },
+                Err(e) => {
+                    eprintln!("Error: Failed to serialize attestation: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Verify {
+            public_key,
+            location,
+            signature,
+            account,
+            challenge,
+            expires_at,
+            nonce,
+            genesis_hash,
+        } => {
+            let public_key = match env::try_hex_to_array(public_key) {
+                Ok(public_key) => Key::new(public_key),
+                Err(e) => {
+                    eprintln!("Error: Failed to parse public key: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let signature = match env::try_hex_to_array::<64>(signature) {
+                Ok(signature) => signature,
+                Err(e) => {
+                    eprintln!("Error: Failed to parse signature: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let account = match env::try_hex_to_array(account) {
+                Ok(account) => account,
+                Err(e) => {
+                    eprintln!("Error: Failed to parse account: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let genesis_hash = match env::try_hex_to_array(genesis_hash) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    eprintln!("Error: Failed to parse genesis hash: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            let payload = AttestationPayload {
+                account,
+                challenge,
+                location: location.into_bytes(),
+                expires_at,
+                nonce,
+                genesis_hash,
+            };
+
+            // Show what was hashed, to compare against the submitted extrinsic
+            println!("Payload=0x{}", env::array_to_hex(payload.encode()));
+            println!(
+                "Hash=0x{}",
+                env::array_to_hex(Blake2_256::hash(payload.encode()).as_bytes())
+            );
+
+            if verify_attestation::<Ed25519, Blake2_256>(public_key, &payload, &signature) {
+                println!("Signature is valid");
+            } else {
+                eprintln!("Error: Signature is invalid for this payload and public key");
+                std::process::exit(1);
+            }
+        }
+        Commands::Merge { files } => {
+            let mut partials = Vec::with_capacity(files.len());
+            for file in files {
+                let partial = std::fs::read(&file)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| {
+                        Attestation::decode_from(&json, Encoding::Json).map_err(|e| e.to_string())
+                    })
+                    .map(PartialAttestation::from);
+                match partial {
+                    Ok(partial) => partials.push(partial),
+                    Err(e) => {
+                        eprintln!("Error: Failed to read {}: {}", file.display(), e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+
+            let bundle = match merge_attestations(partials) {
+                Ok(bundle) => bundle,
+                Err(e) => {
+                    eprintln!("Error: Failed to merge attestations: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            match serde_json::to_string(&bundle) {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("Error: Failed to serialize bundle: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Cover {
+            file,
+            precision,
+            min_precision,
+            max_cells,
+        } => {
+            let geojson = match std::fs::read_to_string(&file) {
+                Ok(geojson) => geojson,
+                Err(e) => {
+                    eprintln!("Error: Failed to read {}: {}", file.display(), e);
+                    std::process::exit(1);
+                }
+            };
+
+            let cells = match geojson_cover(&geojson, min_precision, precision, max_cells) {
+                Ok(cells) => cells,
+                Err(e) => {
+                    eprintln!("Error: Failed to cover polygon: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            match serde_json::to_string(&cells) {
                 Ok(json) => println!("{}", json),
                 Err(e) => {
-                    eprintln!("Error: Failed to serialize signature: {}", e);
+                    eprintln!("Error: Failed to serialize cells: {}", e);
                     std::process::exit(1);
                 }
             }
@@ -0,0 +1,58 @@
+//! Fixed location provider.
+//!
+//! Reports the same coordinates every time, for testing without a GPS receiver
+//! or network access.
+
+use async_trait::async_trait;
+use oracle::{Location, LocationError};
+
+/// Implementation of the `Location` trait for a fixed latitude and longitude.
+pub struct Fixed {
+    /// The latitude in decimal degrees
+    latitude: f64,
+    /// The longitude in decimal degrees
+    longitude: f64,
+}
+
+impl Fixed {
+    /// Creates a provider which always reports the given coordinates.
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+}
+
+#[async_trait]
+impl Location for Fixed {
+    /// The output type is a String representing the geohash.
+    type Output = String;
+
+    /// Encodes the fixed coordinates as a geohash string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LocationError::Output` if the coordinates or accuracy are out of range.
+    async fn current_location(&self, accuracy: u8) -> Result<Self::Output, LocationError> {
+        super::encode(self.latitude, self.longitude, accuracy)
+    }
+}
+
+#[tokio::test]
+async fn test_fixed_location() {
+    let london = Fixed::new(51.5085, -0.1257);
+    assert_eq!(
+        london.current_location(6).await.ok(),
+        Some("gcpvj0".to_string())
+    );
+    assert_eq!(
+        london.current_location(8).await.ok(),
+        Some("gcpvj0u6".to_string())
+    );
+
+    assert!(matches!(
+        Fixed::new(91.0, 0.0).current_location(6).await,
+        Err(LocationError::Output(_))
+    ));
+}
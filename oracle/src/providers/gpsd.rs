@@ -0,0 +1,146 @@
+//! gpsd location provider.
+//!
+//! Connects to the GPS daemon's local socket, asks it to stream reports with
+//! its JSON protocol, and waits for a report with a position fix.
+
+use async_trait::async_trait;
+use oracle::{Location, LocationError};
+use serde::Deserialize;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+/// The address gpsd listens on by default.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:2947";
+
+/// The command asking gpsd to stream JSON reports.
+const WATCH: &[u8] = b"?WATCH={\"enable\":true,\"json\":true};\n";
+
+/// The most reports read while waiting for a fix.
+const MAX_REPORTS: usize = 100;
+
+/// A gpsd report, of which only time-position-velocity (TPV) reports are used.
+#[derive(Deserialize)]
+struct Report {
+    /// The type of report
+    class: String,
+    /// The fix: 0 or 1 without a fix, 2 for a 2D fix and 3 for a 3D fix
+    #[serde(default)]
+    mode: u8,
+    /// The latitude in decimal degrees
+    lat: Option<f64>,
+    /// The longitude in decimal degrees
+    lon: Option<f64>,
+}
+
+/// Implementation of the `Location` trait for gpsd.
+pub struct Gpsd {
+    /// The address of gpsd's socket
+    address: String,
+}
+
+impl Gpsd {
+    /// Creates a provider connecting to gpsd at the given address.
+    pub fn new(address: impl Into<String>) -> Self {
+        Self {
+            address: address.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Location for Gpsd {
+    /// The output type is a String representing the geohash.
+    type Output = String;
+
+    /// Watches gpsd's reports until one has a fix, and encodes its position as
+    /// a geohash string.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - gpsd can't be connected to or read (LocationError::Source)
+    /// - No fix is reported before gpsd disconnects or `MAX_REPORTS` are read
+    ///   (LocationError::Source)
+    /// - Failed to encode the coordinates as a geohash (LocationError::Output)
+    async fn current_location(&self, accuracy: u8) -> Result<Self::Output, LocationError> {
+        let source = |e: std::io::Error| LocationError::Source(format!("{}: {}", self.address, e));
+        let mut stream = TcpStream::connect(&self.address).await.map_err(source)?;
+        stream.write_all(WATCH).await.map_err(source)?;
+
+        let mut lines = BufReader::new(stream).lines();
+        for _ in 0..MAX_REPORTS {
+            let Some(line) = lines.next_line().await.map_err(source)? else {
+                break;
+            };
+            if let Some((latitude, longitude)) = parse_report(&line) {
+                return super::encode(latitude, longitude, accuracy);
+            }
+        }
+
+        Err(LocationError::Source(format!(
+            "{}: no position fix",
+            self.address
+        )))
+    }
+}
+
+/// Parses the position out of a TPV report.
+///
+/// # Returns
+///
+/// * `Option<(f64, f64)>` - A tuple of (latitude, longitude) if the report is
+///   a TPV report with at least a 2D fix, otherwise `None`.
+fn parse_report(report: &str) -> Option<(f64, f64)> {
+    let report: Report = serde_json::from_str(report).ok()?;
+    if report.class != "TPV" || report.mode < 2 {
+        return None;
+    }
+    Some((report.lat?, report.lon?))
+}
+
+#[cfg(test)]
+const FIXTURE: &str = include_str!("../../tests/fixtures/gpsd.jsonl");
+
+#[test]
+fn test_parse_report() {
+    let reports: Vec<_> = FIXTURE.lines().map(parse_report).collect();
+    assert_eq!(
+        reports,
+        [
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some((51.5085, -0.1257)),
+            Some((48.1173, 11.516666667)),
+        ]
+    );
+
+    assert_eq!(parse_report(r#"{"class":"TPV","mode":2}"#), None);
+    assert_eq!(parse_report("not json"), None);
+}
+
+#[tokio::test]
+async fn test_gpsd_session() {
+    use tokio::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+
+    // Replays the recorded session once the client asks to watch
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut watch = String::new();
+        BufReader::new(reader).read_line(&mut watch).await.unwrap();
+        writer.write_all(FIXTURE.as_bytes()).await.unwrap();
+        watch
+    });
+
+    let location = Gpsd::new(address).current_location(8).await;
+    assert_eq!(location.ok(), Some("gcpvj0u6".to_string()));
+    assert_eq!(server.await.unwrap().as_bytes(), WATCH);
+}
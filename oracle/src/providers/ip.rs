@@ -1,10 +1,9 @@
-//! Geohash implementation for location services.
+//! IP geolocation provider.
 //!
 //! This module provides functionality to get the current geographical location
 //! based on IP address and convert it to a geohash string.
 
 use async_trait::async_trait;
-use geohash::Coord;
 use oracle::{Location, LocationError};
 
 /// Module for retrieving geographical location data using IP geolocation.
@@ -15,7 +14,7 @@ mod ip_info {
     use serde::Deserialize;
     /// The base URL for the ipinfo.io API service.
     const IPINFO: &str = "https://ipinfo.io";
-    
+
     /// Structure for deserializing the ipinfo.io API response.
     #[derive(Deserialize)]
     struct IpInfo {
@@ -26,8 +25,7 @@ mod ip_info {
     /// Fetches the current geographical coordinates using IP geolocation.
     ///
     /// Makes an HTTP request to ipinfo.io to determine the current location
-    /// based on the device's IP address. Parses the response and extracts
-    /// latitude and longitude coordinates.
+    /// based on the device's IP address and parses the response.
     ///
     /// # Returns
     ///
@@ -38,12 +36,32 @@ mod ip_info {
     ///
     /// This function will return an error if:
     /// - The HTTP request to ipinfo.io fails
+    /// - The response can't be parsed, see [`parse`]
+    pub async fn get_ip() -> Result<(f64, f64), String> {
+        let response = reqwest::get(IPINFO).await.map_err(|e| e.to_string())?;
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        parse(&body)
+    }
+
+    /// Parses the coordinates out of an ipinfo.io response.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The JSON body of the response
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(f64, f64), String>` - A tuple of (latitude, longitude) if successful,
+    ///   or an error message string if parsing failed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
     /// - The response cannot be parsed as valid JSON
     /// - The location format is invalid (not "latitude,longitude")
     /// - The latitude or longitude values cannot be parsed as valid floating-point numbers
-    pub async fn get_ip() -> Result<(f64, f64), String> {
-        let response = reqwest::get(IPINFO).await.map_err(|e| e.to_string())?;
-        let ip_info: IpInfo = response.json().await.map_err(|e| e.to_string())?;
+    pub fn parse(body: &str) -> Result<(f64, f64), String> {
+        let ip_info: IpInfo = serde_json::from_str(body).map_err(|e| e.to_string())?;
         let parts: Vec<&str> = ip_info.loc.split(',').collect();
         if parts.len() != 2 {
             return Err(format!("Invalid location format: {}", ip_info.loc));
@@ -62,14 +80,16 @@ mod ip_info {
     }
 }
 
-/// Implementation of the `Location` trait using geohash encoding.
+/// Implementation of the `Location` trait using IP geolocation.
 ///
-/// This struct provides functionality to get the current geographical location 
-/// and encode it as a geohash string with variable precision.
-pub struct Geohash;
+/// This struct provides functionality to get the current geographical location
+/// from the device's IP address and encode it as a geohash string with variable
+/// precision. IP geolocation is only accurate to the network operator, so it
+/// can't prove physical attendance.
+pub struct IpInfo;
 
 #[async_trait]
-impl Location for Geohash {
+impl Location for IpInfo {
     /// The output type is a String representing the geohash.
     type Output = String;
 
@@ -94,12 +114,21 @@ impl Location for Geohash {
     /// This function will return an error if:
     /// - Failed to obtain the current location (LocationError::Location)
     /// - Failed to encode the coordinates as a geohash (LocationError::Output)
-    async fn current_location(accuarcy: u8) -> Result<Self::Output, LocationError> {
-        let (x, y) = ip_info::get_ip()
+    async fn current_location(&self, accuarcy: u8) -> Result<Self::Output, LocationError> {
+        let (latitude, longitude) = ip_info::get_ip()
             .await
             .map_err(|_| LocationError::Location)?;
 
-        geohash::encode(Coord { x, y }, accuarcy as usize)
-            .map_err(|e| LocationError::Output(e.to_string()))
+        super::encode(latitude, longitude, accuarcy)
     }
 }
+
+#[test]
+fn test_parse_ip_info() {
+    let body = include_str!("../../tests/fixtures/ipinfo.json");
+    assert_eq!(ip_info::parse(body), Ok((51.5085, -0.1257)));
+
+    assert!(ip_info::parse(r#"{"loc":"51.5085"}"#).is_err());
+    assert!(ip_info::parse(r#"{"loc":"north,west"}"#).is_err());
+    assert!(ip_info::parse(r#"{"ip":"203.0.113.7"}"#).is_err());
+}
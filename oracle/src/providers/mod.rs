@@ -0,0 +1,33 @@
+//! Location providers.
+//!
+//! Each provider implements the `Location` trait for a different source of
+//! coordinates, and encodes the coordinates it reads as a geohash string.
+
+mod fixed;
+mod gpsd;
+mod ip;
+mod nmea;
+
+pub use fixed::Fixed;
+pub use gpsd::{Gpsd, DEFAULT_ADDRESS as GPSD_ADDRESS};
+pub use ip::IpInfo;
+pub use nmea::Nmea;
+
+use oracle::LocationError;
+
+/// Encodes coordinates as a geohash string.
+///
+/// # Arguments
+///
+/// * `latitude` - The latitude in decimal degrees
+/// * `longitude` - The longitude in decimal degrees
+/// * `accuracy` - The length of the geohash (1-12)
+///
+/// # Errors
+///
+/// Returns `LocationError::Output` if the coordinates or accuracy are out of range.
+fn encode(latitude: f64, longitude: f64, accuracy: u8) -> Result<String, LocationError> {
+    geohash_utils::encode(latitude, longitude, accuracy as usize)
+        .and_then(|geohash| String::from_utf8(geohash).ok())
+        .ok_or_else(|| LocationError::Output("invalid coordinates or accuracy".to_string()))
+}
@@ -0,0 +1,177 @@
+//! NMEA 0183 location provider.
+//!
+//! Reads sentences from a GPS receiver's serial device, or from a file they
+//! were recorded to, until one reports a position fix.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use oracle::{Location, LocationError};
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, BufReader},
+};
+
+/// The most sentences read while waiting for a fix.
+///
+/// A receiver sends around ten sentences a second, so this gives up after
+/// a minute and a half without a fix.
+const MAX_SENTENCES: usize = 1_000;
+
+/// Implementation of the `Location` trait for an NMEA 0183 source.
+///
+/// The position is taken from the first GGA sentence with a fix, or RMC
+/// sentence with a valid status, from any talker.
+pub struct Nmea {
+    /// The serial device or recording to read sentences from
+    path: PathBuf,
+}
+
+impl Nmea {
+    /// Creates a provider reading sentences from the given path.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl Location for Nmea {
+    /// The output type is a String representing the geohash.
+    type Output = String;
+
+    /// Reads sentences until one reports a fix, and encodes its position as a
+    /// geohash string.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The source can't be opened or read (LocationError::Source)
+    /// - No fix is reported before the source ends or `MAX_SENTENCES` are read
+    ///   (LocationError::Source)
+    /// - Failed to encode the coordinates as a geohash (LocationError::Output)
+    async fn current_location(&self, accuracy: u8) -> Result<Self::Output, LocationError> {
+        let source =
+            |e: std::io::Error| LocationError::Source(format!("{}: {}", self.path.display(), e));
+        let file = File::open(&self.path).await.map_err(source)?;
+
+        // Serial devices can start mid-sentence, so lines aren't required to be UTF-8
+        let mut lines = BufReader::new(file).split(b'\n');
+        for _ in 0..MAX_SENTENCES {
+            let Some(line) = lines.next_segment().await.map_err(source)? else {
+                break;
+            };
+            if let Some((latitude, longitude)) = parse_sentence(&String::from_utf8_lossy(&line)) {
+                return super::encode(latitude, longitude, accuracy);
+            }
+        }
+
+        Err(LocationError::Source(format!(
+            "{}: no position fix",
+            self.path.display()
+        )))
+    }
+}
+
+/// Parses the position out of a GGA or RMC sentence.
+///
+/// # Returns
+///
+/// * `Option<(f64, f64)>` - A tuple of (latitude, longitude) if the sentence
+///   has a valid checksum and reports a fix, otherwise `None`.
+fn parse_sentence(sentence: &str) -> Option<(f64, f64)> {
+    let (body, checksum) = sentence.trim().strip_prefix('$')?.split_once('*')?;
+    let checksum = u8::from_str_radix(checksum, 16).ok()?;
+    if body.bytes().fold(0, |sum, byte| sum ^ byte) != checksum {
+        return None;
+    }
+
+    let fields: Vec<&str> = body.split(',').collect();
+    // The address is a two character talker, such as GP or GN, followed by the sentence type
+    match fields[0].get(2..)? {
+        // A fix quality of 0 means there's no fix
+        "GGA" if !matches!(fields.get(6), None | Some(&"" | &"0")) => position(fields.get(2..6)?),
+        "RMC" if fields.get(2) == Some(&"A") => position(fields.get(3..7)?),
+        _ => None,
+    }
+}
+
+/// Converts `ddmm.mmmm,N,dddmm.mmmm,E` fields to decimal degrees.
+fn position(fields: &[&str]) -> Option<(f64, f64)> {
+    let [latitude, north_south, longitude, east_west] = fields else {
+        return None;
+    };
+    let latitude = degrees(latitude, 2)?
+        * match *north_south {
+            "N" => 1.0,
+            "S" => -1.0,
+            _ => return None,
+        };
+    let longitude = degrees(longitude, 3)?
+        * match *east_west {
+            "E" => 1.0,
+            "W" => -1.0,
+            _ => return None,
+        };
+    Some((latitude, longitude))
+}
+
+/// Converts degrees and decimal minutes, with `digits` digits of degrees, to
+/// decimal degrees.
+fn degrees(value: &str, digits: usize) -> Option<f64> {
+    let degrees: f64 = value.get(..digits)?.parse().ok()?;
+    let minutes: f64 = value.get(digits..)?.parse().ok()?;
+    (minutes < 60.0).then_some(degrees + minutes / 60.0)
+}
+
+#[test]
+fn test_parse_sentence() {
+    let munich = (48.0 + 7.038 / 60.0, 11.0 + 31.0 / 60.0);
+    assert_eq!(
+        parse_sentence("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47"),
+        Some(munich)
+    );
+    assert_eq!(
+        parse_sentence("$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r\n"),
+        Some(munich)
+    );
+
+    // Bad checksum, no fix, void status, or another sentence type
+    assert_eq!(
+        parse_sentence("$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*48"),
+        None
+    );
+    assert_eq!(
+        parse_sentence("$GPGGA,091544.00,,,,,0,00,99.99,,,,,,*6B"),
+        None
+    );
+    assert_eq!(
+        parse_sentence("$GPRMC,091545.00,V,,,,,,,180926,,,N*75"),
+        None
+    );
+    assert_eq!(parse_sentence("$GPGSV,1,1,00*79"), None);
+    assert_eq!(parse_sentence("GPGSV,1,1,00"), None);
+}
+
+#[tokio::test]
+async fn test_nmea_recording() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+    // Skips the sentences without a fix and the one with a bad checksum
+    let location = Nmea::new(format!("{fixtures}/nmea.log"))
+        .current_location(8)
+        .await;
+    assert_eq!(location.ok(), Some("gcpvj0u6".to_string()));
+
+    assert!(matches!(
+        Nmea::new(format!("{fixtures}/nmea-no-fix.log"))
+            .current_location(8)
+            .await,
+        Err(LocationError::Source(_))
+    ));
+    assert!(matches!(
+        Nmea::new(format!("{fixtures}/missing.log"))
+            .current_location(8)
+            .await,
+        Err(LocationError::Source(_))
+    ));
+}
@@ -1,8 +1,10 @@
+use ark_serialize::CanonicalSerialize;
+use pallet_attendance::Tolerance;
 use sc_service::ChainType;
-use solochain_template_runtime::{AccountId, Signature, WASM_BINARY};
+use solochain_template_runtime::{AccountId, Signature, DAYS, WASM_BINARY};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{ed25519, hashing::blake2_256, sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
 
 // The URL for the telemetry server.
@@ -28,6 +30,29 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
+/// Seed of the oracle key registered on development chains, the same key as printed by
+/// `oracle generate --seed=attendance-dev-oracle`.
+pub const DEV_ORACLE_SEED: &str = "attendance-dev-oracle";
+
+/// Generate the public key of the development oracle.
+pub fn dev_oracle_key() -> ed25519::Public {
+	ed25519::Pair::from_seed(&blake2_256(DEV_ORACLE_SEED.as_bytes())).public()
+}
+
+/// Geohash of the sample challenge on development chains.
+pub const DEV_CHALLENGE_GEOHASH: &str = "gcpvj0";
+
+/// Generate the verifying key of the development circuit for challenges of `precision`
+/// characters, the same key as derived by `geohash_prover::dev_setup`.
+///
+/// Proofs are only accepted for challenges of the precision the active circuit was set up for.
+pub fn dev_verifying_key(precision: usize) -> Vec<u8> {
+	let (_, key) = geohash_prover::dev_setup(precision).expect("the dev circuit is valid; qed");
+	let mut bytes = Vec::new();
+	key.serialize_uncompressed(&mut bytes).expect("writing to a vec can't fail; qed");
+	bytes
+}
+
 /// Generate an Aura authority key.
 pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
@@ -53,6 +78,12 @@ pub fn development_config() -> Result<ChainSpec, String> {
 			get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 			get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 		],
+		// Attendance oracles
+		vec![dev_oracle_key()],
+		// Attendance verifying keys
+		vec![(0, dev_verifying_key(DEV_CHALLENGE_GEOHASH.len()))],
+		// Sample attendance challenges
+		vec![(get_account_id_from_seed::<sr25519::Public>("Alice"), DEV_CHALLENGE_GEOHASH)],
 		true,
 	))
 	.build())
@@ -86,6 +117,12 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 			get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 			get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 		],
+		// Attendance oracles
+		vec![dev_oracle_key()],
+		// Attendance verifying keys
+		vec![(0, dev_verifying_key(DEV_CHALLENGE_GEOHASH.len()))],
+		// Sample attendance challenges
+		vec![(get_account_id_from_seed::<sr25519::Public>("Alice"), DEV_CHALLENGE_GEOHASH)],
 		true,
 	))
 	.build())
@@ -96,6 +133,9 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	oracles: Vec<ed25519::Public>,
+	verifying_keys: Vec<(u32, Vec<u8>)>,
+	challenges: Vec<(AccountId, &str)>,
 	_enable_println: bool,
 ) -> serde_json::Value {
 	serde_json::json!({
@@ -113,5 +153,16 @@ fn testnet_genesis(
 			// Assign network admin rights.
 			"key": Some(root_key),
 		},
+		"attendanceModule": {
+			"oracles": oracles.iter().map(|k| (k.0.to_vec(), b"dev".to_vec())).collect::<Vec<_>>(),
+			"verifyingKeys": verifying_keys,
+			// Open for 30 days, attested by a single oracle.
+			"challenges": challenges
+				.into_iter()
+				.map(|(organizer, geohash)| {
+					(organizer, geohash.as_bytes(), Tolerance::Exact, 30 * DAYS, None::<u32>, 1)
+				})
+				.collect::<Vec<_>>(),
+		},
 	})
 }
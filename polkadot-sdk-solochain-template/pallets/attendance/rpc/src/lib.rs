@@ -0,0 +1,330 @@
+//! JSON-RPC methods for the attendance pallet, in the `attendance` namespace.
+//!
+//! Each method calls into the [`AttendanceRuntimeApi`] at the given block, or the best block if
+//! none is given, and returns the result as JSON.
+
+use std::{fmt::Debug, sync::Arc};
+
+use codec::{Codec, Decode};
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::error::{ErrorObject, ErrorObjectOwned},
+};
+use pallet_attendance::{
+    ChallengeInfo, ChallengeStatus, Tolerance, ATTENDEES_PAGE_SIZE, CHALLENGES_PAGE_SIZE,
+};
+pub use pallet_attendance_runtime_api::AttendanceApi as AttendanceRuntimeApi;
+use pallet_attendance_runtime_api::{ChallengeId, CircuitId};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::Block as BlockT;
+
+/// A challenge and its identifier, as returned over RPC.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeDetails<AccountId, BlockNumber, Balance> {
+    /// The identifier of the challenge
+    pub id: ChallengeId,
+    /// The account which created the challenge
+    pub organizer: AccountId,
+    /// The storage deposit held from the organizer
+    pub deposit: Balance,
+    /// The area attendees must be located in
+    pub geohash: String,
+    /// One of `exact`, `neighbors` or `metres`
+    pub tolerance: String,
+    /// The distance from `geohash` attendees may be located at, if `tolerance` is `metres`
+    pub tolerance_metres: Option<u32>,
+    /// The first block in which submissions are accepted
+    pub opens_at: BlockNumber,
+    /// The block at which the challenge closes
+    pub closes_at: BlockNumber,
+    /// The maximum number of accepted submissions, unlimited if `None`
+    pub max_attendees: Option<u32>,
+    /// The number of accepted submissions
+    pub attendees: u32,
+    /// The number of distinct oracles which must sign a submission
+    pub oracle_threshold: u32,
+    /// The circuit proofs are verified against
+    pub circuit: Option<CircuitId>,
+    /// One of `open`, `closed` or `cancelled`
+    pub status: String,
+}
+
+impl<AccountId, BlockNumber, Balance> ChallengeDetails<AccountId, BlockNumber, Balance> {
+    fn new<Geohash: Into<Vec<u8>>>(
+        id: ChallengeId,
+        info: ChallengeInfo<AccountId, BlockNumber, Geohash, Balance>,
+    ) -> Self {
+        let status = match info.status {
+            ChallengeStatus::Open => "open",
+            ChallengeStatus::Closed => "closed",
+            ChallengeStatus::Cancelled => "cancelled",
+        };
+        let (tolerance, tolerance_metres) = match info.tolerance {
+            Tolerance::Exact => ("exact", None),
+            Tolerance::Neighbors => ("neighbors", None),
+            Tolerance::Metres(metres) => ("metres", Some(metres)),
+        };
+        Self {
+            id,
+            organizer: info.organizer,
+            deposit: info.deposit,
+            geohash: String::from_utf8_lossy(&info.geohash.into()).into_owned(),
+            tolerance: tolerance.into(),
+            tolerance_metres,
+            opens_at: info.opens_at,
+            closes_at: info.closes_at,
+            max_attendees: info.max_attendees,
+            attendees: info.attendees,
+            oracle_threshold: info.oracle_threshold,
+            circuit: info.circuit,
+            status: status.into(),
+        }
+    }
+}
+
+/// The outcome of dry-running a submission.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Validation {
+    /// Whether the submission would be accepted
+    pub valid: bool,
+    /// The error the submission would fail with, if any
+    pub error: Option<String>,
+}
+
+/// A page of results, with the index of the next page if there may be more.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    /// The results in this page
+    pub items: Vec<T>,
+    /// The page to request next, `None` once the last page has been returned
+    pub next_page: Option<u32>,
+}
+
+impl<T> Page<T> {
+    /// A page from a runtime API returning up to `page_size` results per page, which may have
+    /// more results after it if it's full.
+    fn new(items: Vec<T>, page: u32, page_size: u32) -> Self {
+        let next_page = (items.len() as u32 == page_size).then(|| page.saturating_add(1));
+        Self { items, next_page }
+    }
+}
+
+#[rpc(client, server)]
+pub trait AttendanceApi<BlockHash, AccountId, BlockNumber, Balance> {
+    /// The challenge with the given `id`.
+    #[method(name = "attendance_challenge")]
+    fn challenge(
+        &self,
+        id: ChallengeId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<ChallengeDetails<AccountId, BlockNumber, Balance>>>;
+
+    /// A page of the challenges whose geohash starts with `prefix`, or of every challenge.
+    ///
+    /// `prefix` must be at least the runtime's minimum challenge precision long, and pages of
+    /// every challenge may hold fewer than a full page before the last.
+    #[method(name = "attendance_challenges")]
+    fn challenges(
+        &self,
+        prefix: Option<String>,
+        page: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Page<ChallengeDetails<AccountId, BlockNumber, Balance>>>;
+
+    /// A page of the accounts with an accepted submission for `challenge`.
+    #[method(name = "attendance_attendees")]
+    fn attendees(
+        &self,
+        challenge: ChallengeId,
+        page: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Page<AccountId>>;
+
+    /// A page of the challenges `account` has an accepted submission for.
+    #[method(name = "attendance_history")]
+    fn history(
+        &self,
+        account: AccountId,
+        page: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Page<ChallengeDetails<AccountId, BlockNumber, Balance>>>;
+
+    /// Dry-runs the SCALE-encoded submission `call` as if `account` had submitted it.
+    #[method(name = "attendance_validateSubmission")]
+    fn validate_submission(
+        &self,
+        account: AccountId,
+        call: Bytes,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Validation>;
+}
+
+/// Error code returned when a runtime API call fails.
+pub const RUNTIME_ERROR: i32 = 1;
+
+fn runtime_error(error: impl ToString) -> ErrorObjectOwned {
+    ErrorObject::owned(
+        RUNTIME_ERROR,
+        "Unable to query attendance state.",
+        Some(error.to_string()),
+    )
+}
+
+/// Error code returned when the call to validate can't be decoded.
+pub const DECODE_ERROR: i32 = 2;
+
+/// Provides the `attendance` RPC methods.
+///
+/// `Geohash` is the type the runtime stores challenge geohashes as, and `Call` and `Error` are
+/// the pallet's call and submission error types.
+pub struct Attendance<C, Block, Geohash, Call, Error> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<(Block, Geohash, Call, Error)>,
+}
+
+impl<C, Block, Geohash, Call, Error> Attendance<C, Block, Geohash, Call, Error> {
+    /// Creates a new instance of the attendance RPC handler.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block, Geohash, Call, Error, AccountId, BlockNumber, Balance>
+    AttendanceApiServer<<Block as BlockT>::Hash, AccountId, BlockNumber, Balance>
+    for Attendance<C, Block, Geohash, Call, Error>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: AttendanceRuntimeApi<
+        Block,
+        AccountId,
+        ChallengeInfo<AccountId, BlockNumber, Geohash, Balance>,
+        Call,
+        Error,
+    >,
+    Geohash: Codec + Into<Vec<u8>> + Send + Sync + 'static,
+    Call: Codec + Send + Sync + 'static,
+    Error: Codec + Debug + Send + Sync + 'static,
+    AccountId: Codec + Clone + Send + Sync + Serialize + for<'de> Deserialize<'de> + 'static,
+    BlockNumber: Codec + Send + Sync + Serialize + 'static,
+    Balance: Codec + Send + Sync + Serialize + 'static,
+{
+    fn challenge(
+        &self,
+        id: ChallengeId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Option<ChallengeDetails<AccountId, BlockNumber, Balance>>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let info = self
+            .client
+            .runtime_api()
+            .challenge(at, id)
+            .map_err(runtime_error)?;
+        Ok(info.map(|info| ChallengeDetails::new(id, info)))
+    }
+
+    fn challenges(
+        &self,
+        prefix: Option<String>,
+        page: Option<u32>,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Page<ChallengeDetails<AccountId, BlockNumber, Balance>>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let page = page.unwrap_or_default();
+        let api = self.client.runtime_api();
+        let Some(prefix) = prefix else {
+            // Pages of every challenge each cover a range of ids, so may be less than full
+            // before the last
+            let challenges = api.challenges(at, page).map_err(runtime_error)?;
+            let next_id = api.next_challenge_id(at).map_err(runtime_error)?;
+            let next_page = page.saturating_add(1);
+            return Ok(Page {
+                items: challenges
+                    .into_iter()
+                    .map(|(id, info)| ChallengeDetails::new(id, info))
+                    .collect(),
+                next_page: (next_page.saturating_mul(CHALLENGES_PAGE_SIZE) < next_id)
+                    .then_some(next_page),
+            });
+        };
+        let challenges = api
+            .challenges_for_geohash(at, prefix.into_bytes(), page)
+            .map_err(runtime_error)?;
+        let items = challenges
+            .into_iter()
+            .map(|(id, info)| ChallengeDetails::new(id, info))
+            .collect();
+        Ok(Page::new(items, page, CHALLENGES_PAGE_SIZE))
+    }
+
+    fn attendees(
+        &self,
+        challenge: ChallengeId,
+        page: Option<u32>,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Page<AccountId>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let page = page.unwrap_or_default();
+        let attendees = self
+            .client
+            .runtime_api()
+            .attendees(at, challenge, page)
+            .map_err(runtime_error)?;
+        Ok(Page::new(attendees, page, ATTENDEES_PAGE_SIZE))
+    }
+
+    fn history(
+        &self,
+        account: AccountId,
+        page: Option<u32>,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Page<ChallengeDetails<AccountId, BlockNumber, Balance>>> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let page = page.unwrap_or_default();
+        let attended = self
+            .client
+            .runtime_api()
+            .attended(at, account, page)
+            .map_err(runtime_error)?;
+        let items = attended
+            .into_iter()
+            .map(|(id, info)| ChallengeDetails::new(id, info))
+            .collect();
+        Ok(Page::new(items, page, ATTENDEES_PAGE_SIZE))
+    }
+
+    fn validate_submission(
+        &self,
+        account: AccountId,
+        call: Bytes,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Validation> {
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let call = Call::decode(&mut &*call).map_err(|error| {
+            ErrorObject::owned(
+                DECODE_ERROR,
+                "Unable to decode call.",
+                Some(error.to_string()),
+            )
+        })?;
+        let result = self
+            .client
+            .runtime_api()
+            .validate_submission(at, account, call)
+            .map_err(runtime_error)?;
+        Ok(Validation {
+            valid: result.is_ok(),
+            error: result.err().map(|error| format!("{error:?}")),
+        })
+    }
+}
@@ -0,0 +1,52 @@
+//! Runtime API definition for the attendance pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::Codec;
+pub use pallet_attendance::{ChallengeId, CircuitId};
+
+sp_api::decl_runtime_apis! {
+    /// Queries the state of the attendance pallet, so clients don't have to decode raw storage.
+    pub trait AttendanceApi<AccountId, Challenge, Call, Error>
+    where
+        AccountId: Codec,
+        Challenge: Codec,
+        Call: Codec,
+        Error: Codec,
+    {
+        /// The challenge with the given `id`, if it exists.
+        fn challenge(id: ChallengeId) -> Option<Challenge>;
+
+        /// A page of the challenges, starting from 0, each page covering a range of ids.
+        fn challenges(page: u32) -> Vec<(ChallengeId, Challenge)>;
+
+        /// The id the next challenge will be given, one more than the last challenge's.
+        fn next_challenge_id() -> ChallengeId;
+
+        /// A page of the challenges whose geohash starts with `prefix`, starting from 0.
+        ///
+        /// `prefix` must be at least the runtime's minimum challenge precision long.
+        fn challenges_for_geohash(prefix: Vec<u8>, page: u32) -> Vec<(ChallengeId, Challenge)>;
+
+        /// Whether `account` has an accepted submission for `challenge`.
+        fn has_attended(challenge: ChallengeId, account: AccountId) -> bool;
+
+        /// A page of the accounts with an accepted submission for `challenge`, starting from 0.
+        fn attendees(challenge: ChallengeId, page: u32) -> Vec<AccountId>;
+
+        /// A page of the challenges `account` has an accepted submission for, starting from 0.
+        fn attended(account: AccountId, page: u32) -> Vec<(ChallengeId, Challenge)>;
+
+        /// The public keys of the oracles whose signatures are currently accepted.
+        fn oracle_keys() -> Vec<Vec<u8>>;
+
+        /// The active circuit and its verifying key, used by new challenges.
+        fn verifying_key() -> Option<(CircuitId, Vec<u8>)>;
+
+        /// Dry-runs `call` as if `account` submitted it, rolling back every change it makes.
+        fn validate_submission(account: AccountId, call: Call) -> Result<(), Error>;
+    }
+}
@@ -4,31 +4,427 @@ use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Attendance;
+use alloc::{vec, vec::Vec};
+use ark_bn254::{Bn254, Fr};
+use ark_serialize::CanonicalSerialize;
+use codec::Encode;
 use frame_benchmarking::v2::*;
-use frame_system::RawOrigin;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{fungible::Mutate, EnsureOrigin},
+};
+use frame_system::{pallet_prelude::BlockNumberFor, RawOrigin};
+use geohash_prover::{create_proof, setup_groth16, CompareCircuit};
+use rand::{rngs::StdRng, SeedableRng};
+use sp_core::Hasher;
+use sp_runtime::{app_crypto::ByteArray, traits::Saturating};
+
+fn funded<T: Config>(who: &T::AccountId) {
+    let amount = T::Currency::minimum_balance().saturating_mul(1_000_000u32.into());
+    T::Currency::set_balance(who, amount);
+}
+
+fn geohash<T: Config>(length: u32) -> BoundedVec<u8, T::MaxGeohashLength> {
+    cell::<T>(b'b', length)
+}
+
+// A geohash of `length` copies of `character`
+fn cell<T: Config>(character: u8, length: u32) -> BoundedVec<u8, T::MaxGeohashLength> {
+    BoundedVec::try_from(vec![character; length as usize]).expect("length is within bounds")
+}
+
+// The most cells a challenge can have, of which only the last contains `geohash::<T>(_)`
+fn cells<T: Config>(
+    length: u32,
+) -> BoundedVec<BoundedVec<u8, T::MaxGeohashLength>, T::MaxChallengeCells> {
+    let count = T::MaxChallengeCells::get();
+    let cells = (0..count)
+        .map(|index| cell::<T>(if index + 1 == count { b'b' } else { b'c' }, length))
+        .collect::<Vec<_>>();
+    BoundedVec::try_from(cells).expect("count is within bounds")
+}
+
+// Create an open challenge for `geohash` with a budget, so submissions pay a reward
+fn create<T: Config>(
+    organizer: &T::AccountId,
+    geohash: BoundedVec<u8, T::MaxGeohashLength>,
+    oracle_threshold: u32,
+) -> ChallengeId {
+    create_with_cells::<T>(organizer, geohash, Default::default(), oracle_threshold)
+}
+
+fn create_with_cells<T: Config>(
+    organizer: &T::AccountId,
+    geohash: BoundedVec<u8, T::MaxGeohashLength>,
+    cells: BoundedVec<BoundedVec<u8, T::MaxGeohashLength>, T::MaxChallengeCells>,
+    oracle_threshold: u32,
+) -> ChallengeId {
+    let challenge = NextChallengeId::<T>::get();
+    let now = frame_system::Pallet::<T>::block_number();
+    let reward = T::Currency::minimum_balance().saturating_mul(10u32.into());
+    Attendance::<T>::create_challenge(
+        RawOrigin::Signed(organizer.clone()).into(),
+        geohash,
+        Tolerance::Exact,
+        cells,
+        now,
+        now.saturating_add(100u32.into()),
+        None,
+        oracle_threshold,
+        Some(ChallengeBudget {
+            amount: reward.saturating_mul(1_000u32.into()),
+            payout: Payout::FirstCome(reward),
+        }),
+    )
+    .expect("challenge is created");
+    challenge
+}
+
+fn oracle<T: Config>(index: u32) -> BoundedVec<u8, ConstU32<32>> {
+    let (public, _) = T::BenchmarkHelper::sign(index, &[]);
+    BoundedVec::try_from(public.to_raw_vec()).expect("public key is 32 bytes")
+}
+
+fn register_oracle<T: Config>(index: u32) -> BoundedVec<u8, ConstU32<32>> {
+    let oracle = oracle::<T>(index);
+    let origin = T::OracleOrigin::try_successful_origin().expect("origin can add oracles");
+    Attendance::<T>::add_oracle(origin, oracle.clone(), BoundedVec::default())
+        .expect("oracle is added");
+    oracle
+}
+
+// Create a challenge needing `a` oracles and have each of them attest `attendee` is in a geohash
+// of `g` characters, found in the last of the challenge's cells
+fn attested<T: Config>(
+    attendee: &T::AccountId,
+    a: u32,
+    g: u32,
+) -> (
+    ChallengeId,
+    BoundedVec<u8, T::MaxGeohashLength>,
+    BlockNumberFor<T>,
+    BoundedVec<(BoundedVec<u8, ConstU32<32>>, BoundedVec<u8, ConstU32<64>>), T::MaxAttestations>,
+) {
+    let organizer: T::AccountId = account("organizer", 0, 0);
+    funded::<T>(&organizer);
+    let precision = T::MinChallengePrecision::get();
+    let challenge = create_with_cells::<T>(
+        &organizer,
+        cell::<T>(b'c', precision),
+        cells::<T>(precision),
+        a,
+    );
+
+    let location = geohash::<T>(g);
+    let expires_at: BlockNumberFor<T> = frame_system::Pallet::<T>::block_number();
+    let payload = AttestationPayload {
+        account: attendee.clone(),
+        challenge,
+        location: location.clone(),
+        expires_at,
+        nonce: 0,
+        genesis_hash: Attendance::<T>::genesis_hash(),
+    };
+    let message = T::PayloadHasher::hash(&payload.encode());
+    let attestations = (0..a)
+        .map(|index| {
+            let oracle = register_oracle::<T>(index);
+            let (_, signature) = T::BenchmarkHelper::sign(index, message.as_ref());
+            let signature =
+                BoundedVec::try_from(signature.to_raw_vec()).expect("signature is 64 bytes");
+            (oracle, signature)
+        })
+        .collect::<Vec<_>>();
+    let attestations = BoundedVec::try_from(attestations).expect("a is within bounds");
+    (challenge, location, expires_at, attestations)
+}
+
+// Set up a circuit for a challenge in a geohash of `length` characters, returning its verifying
+// key and a proof bound to `binding`
+fn prove(length: u32, compressed: bool, binding: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let geohash = vec![Fr::from(b'b' as u64); length as usize];
+    let circuit = CompareCircuit::new(geohash.clone(), geohash);
+    let mut rng = StdRng::seed_from_u64(0);
+    let (proving_key, verifying_key) =
+        setup_groth16(&mut rng, circuit.clone()).expect("circuit is set up");
+    let proof =
+        create_proof(&proving_key, circuit.bound_to(binding), &mut rng).expect("proof is created");
+
+    let mut key = Vec::new();
+    verifying_key
+        .serialize_uncompressed(&mut key)
+        .expect("key is serialized");
+    let mut bytes = Vec::new();
+    if compressed {
+        ark_groth16::Proof::<Bn254>::serialize_compressed(&proof, &mut bytes)
+    } else {
+        ark_groth16::Proof::<Bn254>::serialize_uncompressed(&proof, &mut bytes)
+    }
+    .expect("proof is serialized");
+    (key, bytes)
+}
 
 #[benchmarks]
 mod benchmarks {
     use super::*;
 
     #[benchmark]
-    fn do_something() {
-        let value = 100u32;
+    fn create_challenge(
+        g: Linear<{ T::MinChallengePrecision::get() }, { T::MaxChallengePrecision::get() }>,
+        c: Linear<0, { T::MaxChallengeCells::get() }>,
+    ) {
+        let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller);
+        let now = frame_system::Pallet::<T>::block_number();
+        let budget = ChallengeBudget {
+            amount: T::Currency::minimum_balance().saturating_mul(1_000u32.into()),
+            payout: Payout::Equal,
+        };
+        // Every block before the last one the challenge can spill over to is full
+        let closes_at = now.saturating_add(100u32.into());
+        let full: BoundedVec<_, T::MaxChallengesPerBlock> = BoundedVec::try_from(vec![
+            ChallengeId::MAX;
+            T::MaxChallengesPerBlock::get() as usize
+        ])
+        .expect("fills the block");
+        for offset in 0..EXPIRY_SPILLOVER_BLOCKS {
+            ChallengeExpiries::<T>::insert(closes_at.saturating_add(offset.into()), full.clone());
+        }
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller),
+            geohash::<T>(g),
+            Tolerance::Metres(T::MaxToleranceMetres::get()),
+            BoundedVec::try_from(vec![geohash::<T>(g); c as usize]).expect("c is within bounds"),
+            now,
+            closes_at,
+            Some(100),
+            1,
+            Some(budget),
+        );
+
+        assert!(Challenges::<T>::contains_key(0));
+        assert_eq!(
+            ChallengeExpiries::<T>::get(closes_at.saturating_add(EXPIRY_SPILLOVER_BLOCKS.into()))
+                .to_vec(),
+            vec![0]
+        );
+    }
+
+    #[benchmark]
+    fn submission_with_signature(
+        a: Linear<1, { T::MaxAttestations::get() }>,
+        g: Linear<{ T::MinChallengePrecision::get() }, { T::MaxGeohashLength::get() }>,
+    ) {
+        let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller);
+        let (challenge, location, expires_at, attestations) = attested::<T>(&caller, a, g);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller.clone()),
+            challenge,
+            location,
+            expires_at,
+            0,
+            attestations,
+        );
+
+        assert!(Submissions::<T>::contains_key(challenge, caller));
+    }
+
+    #[benchmark]
+    fn add_oracle(
+        m: Linear<0, { T::MaxOracleMetadataLength::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let origin =
+            T::OracleOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let oracle = oracle::<T>(0);
+        let metadata = BoundedVec::try_from(vec![0u8; m as usize]).expect("m is within bounds");
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, oracle.clone(), metadata);
+
+        assert!(Oracles::<T>::contains_key(oracle));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn submission_with_proof(
+        g: Linear<{ T::MinChallengePrecision::get() }, { T::MaxChallengePrecision::get() }>,
+        c: Linear<0, 1>,
+    ) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let binding = Attendance::<T>::proof_binding(NextChallengeId::<T>::get(), &caller);
+        let (key, proof) = prove(g, c == 1, binding.as_ref());
+        let origin = T::VerifyingKeyOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        Attendance::<T>::set_proof_verifying_key(
+            origin,
+            0,
+            BoundedVec::try_from(key).expect("key fits"),
+        )
+        .expect("key is set");
+        let organizer: T::AccountId = account("organizer", 0, 0);
+        funded::<T>(&organizer);
+        let challenge = create::<T>(&organizer, geohash::<T>(g), 1);
+        funded::<T>(&caller);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(caller.clone()),
+            challenge,
+            BoundedVec::try_from(proof).expect("proof fits"),
+        );
+
+        assert!(Submissions::<T>::contains_key(challenge, caller));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn close_challenge() {
+        let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller);
+        let challenge = create::<T>(&caller, geohash::<T>(T::MinChallengePrecision::get()), 1);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), challenge);
+
+        assert_eq!(
+            Challenges::<T>::get(challenge).map(|info| info.status),
+            Some(ChallengeStatus::Closed)
+        );
+    }
+
+    #[benchmark]
+    fn cancel_challenge() {
+        let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller);
+        let challenge = create::<T>(&caller, geohash::<T>(T::MinChallengePrecision::get()), 1);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), challenge);
+
+        assert_eq!(
+            Challenges::<T>::get(challenge).map(|info| info.status),
+            Some(ChallengeStatus::Cancelled)
+        );
+    }
+
+    #[benchmark]
+    fn remove_oracle() -> Result<(), BenchmarkError> {
+        let origin =
+            T::OracleOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let oracle = register_oracle::<T>(0);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, oracle.clone());
+
+        assert!(!Oracles::<T>::contains_key(oracle));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn rotate_oracle() -> Result<(), BenchmarkError> {
+        let origin =
+            T::OracleOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let old = register_oracle::<T>(0);
+        let new = oracle::<T>(1);
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, old, new.clone());
+
+        assert!(Oracles::<T>::contains_key(new));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_proof_verifying_key(
+        k: Linear<1, { T::MaxVerifyingKeyLength::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let origin = T::VerifyingKeyOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let key = BoundedVec::try_from(vec![0u8; k as usize]).expect("k is within bounds");
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, 0, key);
+
+        assert_eq!(ActiveCircuit::<T>::get(), Some(0));
+        Ok(())
+    }
+
+    #[benchmark]
+    fn refund_budget() {
         let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller);
+        let challenge = create::<T>(&caller, geohash::<T>(T::MinChallengePrecision::get()), 1);
+        Attendance::<T>::close_challenge(RawOrigin::Signed(caller.clone()).into(), challenge)
+            .expect("challenge is closed");
+
         #[extrinsic_call]
-        do_something(RawOrigin::Signed(caller), value);
+        _(RawOrigin::Signed(caller), challenge);
 
-        assert_eq!(Something::<T>::get(), Some(value));
+        assert!(!Escrows::<T>::contains_key(challenge));
     }
 
     #[benchmark]
-    fn cause_error() {
-        Something::<T>::put(100u32);
+    fn reap_challenge(n: Linear<1, 1_000>) {
         let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller);
+        let challenge = create::<T>(&caller, geohash::<T>(T::MinChallengePrecision::get()), 1);
+        for index in 0..n {
+            let attendee: T::AccountId = account("attendee", index, 0);
+            Submissions::<T>::insert(challenge, attendee, true);
+        }
+        Attendance::<T>::close_challenge(RawOrigin::Signed(caller.clone()).into(), challenge)
+            .expect("challenge is closed");
+
         #[extrinsic_call]
-        cause_error(RawOrigin::Signed(caller));
+        _(RawOrigin::Signed(caller), challenge, n);
+
+        assert!(!Challenges::<T>::contains_key(challenge));
+    }
+
+    #[benchmark]
+    fn unsigned_submission_with_signature(
+        a: Linear<1, { T::MaxAttestations::get() }>,
+        g: Linear<{ T::MinChallengePrecision::get() }, { T::MaxGeohashLength::get() }>,
+    ) {
+        let attendee: T::AccountId = account("attendee", 0, 0);
+        let (challenge, location, expires_at, attestations) = attested::<T>(&attendee, a, g);
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::None,
+            attendee.clone(),
+            challenge,
+            location,
+            expires_at,
+            0,
+            attestations,
+        );
+
+        assert!(Submissions::<T>::contains_key(challenge, attendee));
+    }
+
+    #[benchmark]
+    fn on_initialize(n: Linear<0, { T::MaxChallengesPerBlock::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        funded::<T>(&caller);
+        let challenges = (0..n)
+            .map(|_| create::<T>(&caller, geohash::<T>(T::MinChallengePrecision::get()), 1))
+            .collect::<Vec<_>>();
+        let closes_at = frame_system::Pallet::<T>::block_number().saturating_add(100u32.into());
+
+        #[block]
+        {
+            Attendance::<T>::on_initialize(closes_at);
+        }
 
-        assert_eq!(Something::<T>::get(), Some(101u32));
+        assert!(challenges.iter().all(|challenge| {
+            Challenges::<T>::get(challenge).map(|info| info.status) == Some(ChallengeStatus::Closed)
+        }));
     }
 
     impl_benchmark_test_suite!(Attendance, crate::mock::new_test_ext(), crate::mock::Test);
@@ -39,6 +39,8 @@
 // We make sure this pallet uses `no_std` for compiling to Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
 
@@ -50,6 +52,7 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod rewards;
 pub mod weights;
 pub use weights::*;
 
@@ -59,13 +62,93 @@ pub mod pallet {
     // Import various useful types required by all FRAME pallets.
     use super::*;
     use frame_support::pallet_prelude::*;
-    use frame_system::{ensure_signed, pallet_prelude::*};
+    use frame_support::storage::with_transaction;
+    use frame_support::traits::{
+        fungible::{self, MutateHold},
+        tokens::{Fortitude, Precision, Restriction},
+        PalletInfoAccess, UnfilteredDispatchable,
+    };
+    use frame_support::PalletId;
+    use frame_system::{ensure_none, ensure_signed, pallet_prelude::*};
     use sp_core::crypto::{Pair, Public, Signature};
     use sp_core::Hasher;
     use sp_runtime::app_crypto::ByteArray;
+    use sp_runtime::traits::{AccountIdConversion, CheckedDiv, Saturating, Zero};
+    use sp_runtime::ArithmeticError;
+    use sp_runtime::SaturatedConversion;
+    use sp_runtime::Vec;
+    use sp_runtime::{ModuleError, TransactionOutcome};
+
+    /// Rewards attendees of a challenge.
+    ///
+    /// An error from either function fails the call that triggered it, so nothing is stored for
+    /// a challenge or submission which couldn't be rewarded.
+    pub trait Mintable<AccountId> {
+        /// Called when `organizer` creates `challenge`, before any attendee is rewarded.
+        fn create_collection(challenge: ChallengeId, organizer: &AccountId) -> DispatchResult;
+        /// Rewards `account`, the `attendee`th accepted submission to `challenge` in `geohash`.
+        fn mint(
+            account: &AccountId,
+            challenge: ChallengeId,
+            attendee: u32,
+            geohash: &[u8],
+        ) -> DispatchResult;
+        /// Called when `challenge`, organized by `organizer`, is reaped, after its last attendee.
+        fn reap_collection(challenge: ChallengeId, organizer: &AccountId) -> DispatchResult;
+        /// The weight of `create_collection`.
+        fn create_collection_weight() -> Weight;
+        /// The weight of `mint`.
+        fn mint_weight() -> Weight;
+        /// The weight of `reap_collection`.
+        fn reap_collection_weight() -> Weight;
+    }
+
+    #[impl_trait_for_tuples::impl_for_tuples(5)]
+    impl<AccountId> Mintable<AccountId> for Tuple {
+        fn create_collection(challenge: ChallengeId, organizer: &AccountId) -> DispatchResult {
+            for_tuples!( #( Tuple::create_collection(challenge, organizer)?; )* );
+            Ok(())
+        }
+
+        fn mint(
+            account: &AccountId,
+            challenge: ChallengeId,
+            attendee: u32,
+            geohash: &[u8],
+        ) -> DispatchResult {
+            for_tuples!( #( Tuple::mint(account, challenge, attendee, geohash)?; )* );
+            Ok(())
+        }
+
+        fn reap_collection(challenge: ChallengeId, organizer: &AccountId) -> DispatchResult {
+            for_tuples!( #( Tuple::reap_collection(challenge, organizer)?; )* );
+            Ok(())
+        }
+
+        fn create_collection_weight() -> Weight {
+            let mut weight = Weight::zero();
+            for_tuples!( #( weight.saturating_accrue(Tuple::create_collection_weight()); )* );
+            weight
+        }
+
+        fn mint_weight() -> Weight {
+            let mut weight = Weight::zero();
+            for_tuples!( #( weight.saturating_accrue(Tuple::mint_weight()); )* );
+            weight
+        }
+
+        fn reap_collection_weight() -> Weight {
+            let mut weight = Weight::zero();
+            for_tuples!( #( weight.saturating_accrue(Tuple::reap_collection_weight()); )* );
+            weight
+        }
+    }
 
-    pub trait Mintable<T> {
-        fn mint(account: &T);
+    /// Provides oracle signatures for benchmarks, which can't hold keys of their own.
+    #[cfg(feature = "runtime-benchmarks")]
+    pub trait BenchmarkHelper<Public, Signature> {
+        /// Signs `message` with the `index`th oracle key, returning the key and the signature.
+        fn sign(index: u32, message: &[u8]) -> (Public, Signature);
     }
 
     // The `Pallet` struct serves as a placeholder to implement traits, methods and dispatchables
@@ -73,11 +156,234 @@ pub mod pallet {
     #[pallet::pallet]
     pub struct Pallet<T>(_);
 
-    type Challenge<T> = BoundedVec<u8, <T as pallet::Config>::MaxGeohashLength>;
+    /// Identifier of a challenge, assigned incrementally on creation.
+    pub type ChallengeId = u32;
+
+    /// Identifier of a proof circuit, each with its own verifying key.
+    pub type CircuitId = u32;
+
+    /// The number of entries in each page returned by [`Pallet::attendees`] and
+    /// [`Pallet::attended`].
+    pub const ATTENDEES_PAGE_SIZE: u32 = 100;
+
+    /// The number of entries in each page returned by [`Pallet::challenges`] and
+    /// [`Pallet::challenges_for_geohash`].
+    pub const CHALLENGES_PAGE_SIZE: u32 = 50;
+
+    /// The number of blocks after `closes_at` a challenge can be scheduled to close in when
+    /// `MaxChallengesPerBlock` challenges already close at `closes_at`.
+    pub const EXPIRY_SPILLOVER_BLOCKS: u32 = 16;
+
+    type Geohash<T> = BoundedVec<u8, <T as pallet::Config>::MaxGeohashLength>;
     type RawPublicKey = BoundedVec<u8, ConstU32<32>>;
     type RawSignature = BoundedVec<u8, ConstU32<64>>;
-    type RawVerifyingKey = BoundedVec<u8, ConstU32<64>>;
-    type RawProof = BoundedVec<u8, ConstU32<64>>;
+    type RawVerifyingKey<T> = BoundedVec<u8, <T as pallet::Config>::MaxVerifyingKeyLength>;
+    type RawProof<T> = BoundedVec<u8, <T as pallet::Config>::MaxProofLength>;
+    type Cells<T> = BoundedVec<Geohash<T>, <T as pallet::Config>::MaxChallengeCells>;
+    type Attestations<T> =
+        BoundedVec<(RawPublicKey, RawSignature), <T as pallet::Config>::MaxAttestations>;
+    /// Where `reap_challenge` left off clearing a challenge's storage.
+    type ReapCursor = BoundedVec<u8, ConstU32<256>>;
+
+    /// The storage `reap_challenge` clears for a challenge, in the order it is cleared.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum ReapStage {
+        Submissions,
+        UsedNonces,
+        ProofNullifiers,
+    }
+
+    /// Why [`Pallet::validate_submission`] rejected a submission.
+    #[derive(Encode, Decode, RuntimeDebugNoBound, TypeInfo)]
+    #[scale_info(skip_type_params(T))]
+    pub enum SubmissionError<T: Config> {
+        /// The submission failed with an error of this pallet.
+        Attendance(Error<T>),
+        /// The submission failed in another pallet, such as minting its badge.
+        Dispatch(DispatchError),
+    }
+
+    impl<T: Config> From<Error<T>> for SubmissionError<T> {
+        fn from(error: Error<T>) -> Self {
+            Self::Attendance(error)
+        }
+    }
+
+    impl<T: Config> From<DispatchError> for SubmissionError<T> {
+        fn from(error: DispatchError) -> Self {
+            if let DispatchError::Module(ModuleError {
+                index,
+                error: bytes,
+                ..
+            }) = error
+            {
+                if index as usize == <Pallet<T> as PalletInfoAccess>::index() {
+                    if let Ok(error) = Error::<T>::decode(&mut &bytes[..]) {
+                        return Self::Attendance(error);
+                    }
+                }
+            }
+            Self::Dispatch(error)
+        }
+    }
+
+    impl<T: Config> From<SubmissionError<T>> for DispatchError {
+        fn from(error: SubmissionError<T>) -> Self {
+            match error {
+                SubmissionError::Attendance(error) => error.into(),
+                SubmissionError::Dispatch(error) => error,
+            }
+        }
+    }
+
+    /// The payload an oracle signs to attest that `account` was at `location` for `challenge`.
+    ///
+    /// The payload is SCALE encoded and hashed with `Config::PayloadHasher` before it is signed,
+    /// binding the signature to a single account, challenge and chain so it can't be replayed.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    pub struct AttestationPayload<AccountId, Geohash, BlockNumber, Hash> {
+        /// The account the attestation was issued to
+        pub account: AccountId,
+        /// The challenge being attended
+        pub challenge: ChallengeId,
+        /// The location reported by the oracle
+        pub location: Geohash,
+        /// The last block in which the attestation can be submitted
+        pub expires_at: BlockNumber,
+        /// A value chosen by the oracle, unique per account
+        pub nonce: u64,
+        /// Hash of the genesis block of the chain the attestation is for
+        pub genesis_hash: Hash,
+    }
+
+    /// The lifecycle status of a challenge.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum ChallengeStatus {
+        /// Submissions are accepted while inside the challenge window
+        Open,
+        /// The window has passed or the organizer closed the challenge
+        Closed,
+        /// The organizer cancelled the challenge
+        Cancelled,
+    }
+
+    /// How far from the challenge cell a location attested by oracles may be.
+    ///
+    /// Proofs of location only prove the challenge cell itself, whatever the tolerance.
+    #[derive(
+        Encode,
+        Decode,
+        Clone,
+        Copy,
+        Default,
+        PartialEq,
+        Eq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+        serde::Serialize,
+        serde::Deserialize,
+    )]
+    pub enum Tolerance {
+        /// The location must be inside the challenge cell
+        #[default]
+        Exact,
+        /// The location may also be in one of the 8 cells around the challenge cell
+        Neighbors,
+        /// The location may be in any cell whose edge is less than this many metres from the
+        /// challenge cell, up to `MaxToleranceMetres`
+        Metres(u32),
+    }
+
+    /// Details of a challenge created by an organizer.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct ChallengeInfo<AccountId, BlockNumber, Geohash, Balance> {
+        /// The account which created the challenge
+        pub organizer: AccountId,
+        /// The storage deposit held from the organizer, returned once the challenge is reaped
+        pub deposit: Balance,
+        /// The area attendees must be located in
+        pub geohash: Geohash,
+        /// How far outside of `geohash` attendees may be located
+        pub tolerance: Tolerance,
+        /// The first block in which submissions are accepted
+        pub opens_at: BlockNumber,
+        /// The block at which the challenge closes, submissions are no longer accepted
+        pub closes_at: BlockNumber,
+        /// The maximum number of accepted submissions, unlimited if `None`
+        pub max_attendees: Option<u32>,
+        /// The number of accepted submissions
+        pub attendees: u32,
+        /// The number of distinct oracles which must sign a submission
+        pub oracle_threshold: u32,
+        /// The circuit proofs are verified against, active when the challenge was created
+        pub circuit: Option<CircuitId>,
+        /// The current status of the challenge
+        pub status: ChallengeStatus,
+    }
+
+    pub type ChallengeInfoOf<T> = ChallengeInfo<
+        <T as frame_system::Config>::AccountId,
+        BlockNumberFor<T>,
+        Geohash<T>,
+        BalanceOf<T>,
+    >;
+
+    /// Details of a registered oracle.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct OracleInfo<BlockNumber, Metadata> {
+        /// Free-form description of the oracle operator
+        pub metadata: Metadata,
+        /// The key the operator first registered with, kept across rotations
+        pub operator: RawPublicKey,
+        /// The block the oracle was registered in
+        pub activated_at: BlockNumber,
+        /// The block from which the oracle's signatures are no longer accepted, set on rotation
+        pub retires_at: Option<BlockNumber>,
+    }
+
+    pub type OracleInfoOf<T> = OracleInfo<
+        BlockNumberFor<T>,
+        BoundedVec<u8, <T as pallet::Config>::MaxOracleMetadataLength>,
+    >;
+
+    /// How a challenge's budget is paid out to attendees.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum Payout<Balance> {
+        /// Split the budget equally between the challenge's `max_attendees`
+        Equal,
+        /// Pay each attendee a fixed amount until the budget runs out
+        FirstCome(Balance),
+    }
+
+    /// A budget put up by the organizer when creating a challenge.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct ChallengeBudget<Balance> {
+        /// The amount held from the organizer
+        pub amount: Balance,
+        /// How the amount is paid out
+        pub payout: Payout<Balance>,
+    }
+
+    /// The part of a challenge's budget which hasn't been paid out yet.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Escrow<Balance> {
+        /// The amount still held from the organizer
+        pub remaining: Balance,
+        /// The amount paid to each attendee
+        pub per_attendee: Balance,
+    }
+
+    pub type BalanceOf<T> = <<T as Config>::Currency as fungible::Inspect<
+        <T as frame_system::Config>::AccountId,
+    >>::Balance;
+
+    pub type AttestationPayloadOf<T> = AttestationPayload<
+        <T as frame_system::Config>::AccountId,
+        Geohash<T>,
+        BlockNumberFor<T>,
+        <T as frame_system::Config>::Hash,
+    >;
 
     /// The pallet's configuration trait.
     #[pallet::config]
@@ -94,24 +400,196 @@ pub mod pallet {
         type Signature: Signature;
         /// Verification
         type Verify: Pair<Public = Self::PublicKeyOfOracle, Signature = Self::Signature>;
-        /// Mint to Account
+        /// Rewards attendees of challenges
         type Mint: Mintable<Self::AccountId>;
+        /// The pallet id, used to derive the reward pot account of each challenge
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+        /// The currency challenge budgets are held in
+        type Currency: fungible::Mutate<Self::AccountId>
+            + fungible::MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+        /// The overarching hold reason.
+        type RuntimeHoldReason: From<HoldReason>;
+        /// The base deposit held from the organizer for storing a challenge
+        #[pallet::constant]
+        type ChallengeDepositBase: Get<BalanceOf<Self>>;
+        /// The additional deposit held per byte of challenge storage
+        #[pallet::constant]
+        type DepositPerByte: Get<BalanceOf<Self>>;
         /// Maximum length allowed for geohash
         type MaxGeohashLength: Get<u32>;
+        /// Minimum number of geohash characters of a challenge, so a challenge can't span most
+        /// of the world
+        #[pallet::constant]
+        type MinChallengePrecision: Get<u32>;
+        /// Maximum number of geohash characters of a challenge, at most `MaxGeohashLength`
+        #[pallet::constant]
+        type MaxChallengePrecision: Get<u32>;
+        /// Maximum number of cells, besides its geohash, a challenge's area can cover
+        #[pallet::constant]
+        type MaxChallengeCells: Get<u32>;
+        /// Maximum distance in metres a challenge's `Tolerance::Metres` can accept submissions from
+        #[pallet::constant]
+        type MaxToleranceMetres: Get<u32>;
+        /// Maximum number of challenges which can close in the same block
+        type MaxChallengesPerBlock: Get<u32>;
+        /// Origin allowed to manage the oracle registry
+        type OracleOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Maximum length of the metadata stored for an oracle
+        type MaxOracleMetadataLength: Get<u32>;
+        /// Number of blocks a rotated oracle key is still accepted for
+        type OracleRotationPeriod: Get<BlockNumberFor<Self>>;
+        /// Maximum number of oracle signatures a submission can carry
+        type MaxAttestations: Get<u32>;
+        /// Origin allowed to set proof verifying keys
+        type VerifyingKeyOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+        /// Maximum length of a serialized Groth16 proof, 256 bytes for an uncompressed BN254 proof
+        type MaxProofLength: Get<u32>;
+        /// Maximum length of a serialized Groth16 verifying key, which grows by one curve point
+        /// per public input
+        type MaxVerifyingKeyLength: Get<u32>;
+        /// Priority of unsigned submissions in the transaction pool, less one for each block
+        /// until their challenge closes
+        #[pallet::constant]
+        type UnsignedPriority: Get<TransactionPriority>;
+        /// Signs attestations for the benchmarks
+        #[cfg(feature = "runtime-benchmarks")]
+        type BenchmarkHelper: BenchmarkHelper<Self::PublicKeyOfOracle, Self::Signature>;
     }
 
+    /// The identifier the next created challenge will be given.
+    #[pallet::storage]
+    pub type NextChallengeId<T: Config> = StorageValue<_, ChallengeId, ValueQuery>;
+
+    #[pallet::storage]
+    pub type Challenges<T: Config> = StorageMap<_, Twox64Concat, ChallengeId, ChallengeInfoOf<T>>;
+
+    /// The geohash of each challenge, keyed by its first `MinChallengePrecision` characters so
+    /// challenges can be found by area without reading every challenge.
+    #[pallet::storage]
+    pub type ChallengesByArea<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, Geohash<T>, Twox64Concat, ChallengeId, Geohash<T>>;
+
+    /// The challenges each account has an accepted submission for.
+    #[pallet::storage]
+    pub type AttendedChallenges<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, ChallengeId, ()>;
+
+    /// Challenges scheduled to close at a block, which may be after their `closes_at` if that
+    /// block was full.
+    #[pallet::storage]
+    pub type ChallengeExpiries<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        BoundedVec<ChallengeId, T::MaxChallengesPerBlock>,
+        ValueQuery,
+    >;
+
+    /// Cells covered by a challenge's area besides its geohash, such as a cover of a venue's
+    /// outline, by challenge.
+    #[pallet::storage]
+    pub type ChallengeCells<T: Config> = StorageMap<_, Twox64Concat, ChallengeId, Cells<T>>;
+
+    /// Budgets held from organizers to pay attendees, by challenge.
+    #[pallet::storage]
+    pub type Escrows<T: Config> = StorageMap<_, Twox64Concat, ChallengeId, Escrow<BalanceOf<T>>>;
+
+    /// Challenges part way through being reaped, with the stage reached and where it left off.
     #[pallet::storage]
-    pub type Challenges<T: Config> = StorageMap<_, Blake2_128Concat, Challenge<T>, bool>;
+    pub type ReapCursors<T: Config> =
+        StorageMap<_, Twox64Concat, ChallengeId, (ReapStage, Option<ReapCursor>)>;
 
+    /// Oracles whose signatures are accepted, keyed by public key.
     #[pallet::storage]
-    pub type Oracle<T: Config> = StorageValue<_, RawPublicKey>;
+    pub type Oracles<T: Config> = StorageMap<_, Blake2_128Concat, RawPublicKey, OracleInfoOf<T>>;
 
     #[pallet::storage]
     pub type Submissions<T: Config> =
-        StorageDoubleMap<_, Blake2_128Concat, Challenge<T>, Blake2_128Concat, T::AccountId, bool>;
+        StorageDoubleMap<_, Twox64Concat, ChallengeId, Blake2_128Concat, T::AccountId, bool>;
 
+    /// Attestation nonces which have already been used by an account, by challenge.
     #[pallet::storage]
-    pub type ProofVerifyingKey<T: Config> = StorageValue<_, RawVerifyingKey>;
+    pub type UsedNonces<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, ChallengeId, Blake2_128Concat, (T::AccountId, u64), ()>;
+
+    /// Groth16 verifying keys, keyed by the circuit they verify.
+    #[pallet::storage]
+    pub type VerifyingKeys<T: Config> = StorageMap<_, Twox64Concat, CircuitId, RawVerifyingKey<T>>;
+
+    /// The circuit new challenges verify proofs against.
+    #[pallet::storage]
+    pub type ActiveCircuit<T: Config> = StorageValue<_, CircuitId>;
+
+    /// Hashes of the proofs which have already been accepted, so a proof can't be reused, by
+    /// challenge.
+    #[pallet::storage]
+    pub type ProofNullifiers<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, ChallengeId, Blake2_128Concat, T::Hash, ()>;
+
+    #[pallet::genesis_config]
+    #[derive(DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        /// Verifying keys to register, the last of which becomes the active circuit
+        pub verifying_keys: Vec<(CircuitId, RawVerifyingKey<T>)>,
+        /// Oracle public keys to register, with their metadata
+        pub oracles: Vec<(RawPublicKey, BoundedVec<u8, T::MaxOracleMetadataLength>)>,
+        /// Challenges to create, open from genesis, as `(organizer, geohash, tolerance,
+        /// closes_at, max_attendees, oracle_threshold)`
+        pub challenges: Vec<(
+            T::AccountId,
+            Geohash<T>,
+            Tolerance,
+            BlockNumberFor<T>,
+            Option<u32>,
+            u32,
+        )>,
+        #[serde(skip)]
+        pub _config: core::marker::PhantomData<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            for (circuit, key) in &self.verifying_keys {
+                assert!(
+                    !VerifyingKeys::<T>::contains_key(circuit),
+                    "duplicate circuit in genesis"
+                );
+                VerifyingKeys::<T>::insert(circuit, key);
+                ActiveCircuit::<T>::put(circuit);
+            }
+            for (oracle, metadata) in &self.oracles {
+                Pallet::<T>::register_oracle(oracle, oracle.clone(), metadata.clone())
+                    .expect("invalid oracle in genesis");
+            }
+            for (organizer, geohash, tolerance, closes_at, max_attendees, oracle_threshold) in
+                &self.challenges
+            {
+                Pallet::<T>::create_challenge(
+                    frame_system::RawOrigin::Signed(organizer.clone()).into(),
+                    geohash.clone(),
+                    *tolerance,
+                    Default::default(),
+                    Zero::zero(),
+                    *closes_at,
+                    *max_attendees,
+                    *oracle_threshold,
+                    None,
+                )
+                .expect("invalid challenge in genesis");
+            }
+        }
+    }
+
+    /// A reason for the pallet holding funds.
+    #[pallet::composite_enum]
+    pub enum HoldReason {
+        /// The budget of a challenge, paid out to its attendees
+        ChallengeBudget,
+        /// The storage deposit of a challenge, returned when it is reaped
+        ChallengeDeposit,
+    }
 
     /// Events that functions in this pallet can emit.
     ///
@@ -120,12 +598,65 @@ pub mod pallet {
     pub enum Event<T: Config> {
         ChallengeCreated {
             who: T::AccountId,
-            challenge: Challenge<T>,
+            challenge: ChallengeId,
+            geohash: Geohash<T>,
+            opens_at: BlockNumberFor<T>,
+            closes_at: BlockNumberFor<T>,
+        },
+        ChallengeClosed {
+            challenge: ChallengeId,
+        },
+        ChallengeCancelled {
+            challenge: ChallengeId,
         },
         SubmissionAccepted {
             who: T::AccountId,
-            challenge: Challenge<T>,
-            signature: RawSignature,
+            challenge: ChallengeId,
+            attestations: Attestations<T>,
+        },
+        OracleAdded {
+            oracle: RawPublicKey,
+        },
+        OracleRemoved {
+            oracle: RawPublicKey,
+        },
+        OracleRotated {
+            old: RawPublicKey,
+            new: RawPublicKey,
+            retires_at: BlockNumberFor<T>,
+        },
+        ProofSubmissionAccepted {
+            who: T::AccountId,
+            challenge: ChallengeId,
+            nullifier: T::Hash,
+        },
+        BudgetFunded {
+            challenge: ChallengeId,
+            organizer: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        RewardPaid {
+            challenge: ChallengeId,
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        BudgetRefunded {
+            challenge: ChallengeId,
+            organizer: T::AccountId,
+            amount: BalanceOf<T>,
+        },
+        VerifyingKeySet {
+            circuit: CircuitId,
+            key: RawVerifyingKey<T>,
+        },
+        SubmissionsReaped {
+            challenge: ChallengeId,
+            removed: u32,
+        },
+        ChallengeReaped {
+            challenge: ChallengeId,
+            organizer: T::AccountId,
+            deposit: BalanceOf<T>,
         },
     }
 
@@ -138,133 +669,1076 @@ pub mod pallet {
         InvalidSignature,
         AlreadySubmitted,
         InvalidProof,
+        /// The attestation is past its expiry block.
+        AttestationExpired,
+        /// The attestation nonce has already been used by this account.
+        NonceAlreadyUsed,
+        /// The challenge does not exist.
+        UnknownChallenge,
+        /// The challenge window is empty or already over.
+        InvalidWindow,
+        /// The challenge is not accepting submissions.
+        ChallengeNotOpen,
+        /// The challenge has reached its maximum number of attendees.
+        ChallengeFull,
+        /// Only the organizer of the challenge can do this.
+        NotOrganizer,
+        /// Too many challenges close in the block the challenge closes in and in each of the
+        /// `EXPIRY_SPILLOVER_BLOCKS` after it.
+        TooManyExpiries,
+        /// The oracle is not registered or no longer active.
+        NoOracle,
+        /// The oracle is already registered.
+        OracleAlreadyRegistered,
+        /// The oracle threshold is zero or above `MaxAttestations`.
+        InvalidThreshold,
+        /// The same oracle operator signed the submission more than once, possibly with
+        /// both its old and new keys during a rotation.
+        DuplicateOracle,
+        /// Fewer oracles signed the submission than the challenge requires.
+        ThresholdNotMet,
+        /// A verifying key is already registered for the circuit.
+        CircuitAlreadyRegistered,
+        /// The challenge has no verifying key to check proofs against.
+        MissingVerifyingKey,
+        /// The proof could not be deserialized.
+        MalformedProof,
+        /// The stored verifying key could not be deserialized.
+        MalformedVerifyingKey,
+        /// The verifying key does not expect one public input per geohash character followed by
+        /// the submitter's binding.
+        PublicInputMismatch,
+        /// The proof has already been used for a submission.
+        ProofAlreadyUsed,
+        /// The budget is empty, or each attendee's share is below the existential deposit.
+        InvalidBudget,
+        /// An equal split needs the challenge to have a maximum number of attendees.
+        UnboundedAttendees,
+        /// The challenge has no budget left to refund.
+        NoBudget,
+        /// The challenge is still accepting submissions.
+        ChallengeStillOpen,
+        /// The call being validated is not a submission.
+        NotASubmission,
+        /// The challenge geohash is shorter than `MinChallengePrecision` or longer than
+        /// `MaxChallengePrecision`.
+        InvalidPrecision,
+        /// The challenge's distance tolerance is more than `MaxToleranceMetres`.
+        InvalidTolerance,
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            let Call::unsigned_submission_with_signature {
+                attendee,
+                challenge,
+                location,
+                expires_at,
+                nonce,
+                attestations,
+            } = call
+            else {
+                return InvalidTransaction::Call.into();
+            };
+            let now = frame_system::Pallet::<T>::block_number();
+            let info = Self::validate_signature_submission(
+                attendee,
+                *challenge,
+                location,
+                *expires_at,
+                *nonce,
+                attestations,
+            )
+            .map_err(|error| match error {
+                Error::<T>::InvalidSignature
+                | Error::<T>::InvalidPublicKey
+                | Error::<T>::NoOracle
+                | Error::<T>::DuplicateOracle
+                | Error::<T>::ThresholdNotMet => InvalidTransaction::BadProof,
+                Error::<T>::ChallengeNotOpen
+                    if Challenges::<T>::get(challenge).is_some_and(|info| {
+                        info.status == ChallengeStatus::Open && now < info.opens_at
+                    }) =>
+                {
+                    InvalidTransaction::Future
+                }
+                Error::<T>::ChallengeNotOpen
+                | Error::<T>::AlreadySubmitted
+                | Error::<T>::NonceAlreadyUsed
+                | Error::<T>::AttestationExpired
+                | Error::<T>::ChallengeFull => InvalidTransaction::Stale,
+                _ => InvalidTransaction::Call,
+            })?;
+
+            // Drop the submission from the pool once either the attestation or the challenge
+            // expires
+            let longevity = (*expires_at)
+                .min(info.closes_at)
+                .saturating_sub(now)
+                .saturated_into::<u64>()
+                .max(1);
+            // Favour submissions to challenges closing soonest, which can't wait for a later block
+            let priority = T::UnsignedPriority::get()
+                .saturating_sub(info.closes_at.saturating_sub(now).saturated_into());
+            ValidTransaction::with_tag_prefix("Attendance")
+                .priority(priority)
+                .and_provides((challenge, attendee))
+                .longevity(longevity)
+                .propagate(true)
+                .build()
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let expiring = ChallengeExpiries::<T>::take(n);
+            let count = expiring.len() as u32;
+            for challenge in expiring {
+                Challenges::<T>::mutate(challenge, |info| {
+                    if let Some(info) = info.as_mut().filter(|i| i.status == ChallengeStatus::Open)
+                    {
+                        info.status = ChallengeStatus::Closed;
+                        Self::deposit_event(Event::ChallengeClosed { challenge });
+                    }
+                });
+            }
+            T::WeightInfo::on_initialize(count)
+        }
+
+        fn integrity_test() {
+            assert!(
+                1 <= T::MinChallengePrecision::get()
+                    && T::MinChallengePrecision::get() <= T::MaxChallengePrecision::get()
+                    && T::MaxChallengePrecision::get() <= T::MaxGeohashLength::get(),
+                "challenge precision must be within 1..=MaxGeohashLength"
+            );
+        }
     }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
-        #[pallet::weight(0)]
-        pub fn create_challenge(origin: OriginFor<T>, challenge: Challenge<T>) -> DispatchResult {
+        #[pallet::weight(
+            T::WeightInfo::create_challenge(geohash.len() as u32, cells.len() as u32)
+                .saturating_add(T::Mint::create_collection_weight())
+        )]
+        pub fn create_challenge(
+            origin: OriginFor<T>,
+            geohash: Geohash<T>,
+            tolerance: Tolerance,
+            cells: Cells<T>,
+            opens_at: BlockNumberFor<T>,
+            closes_at: BlockNumberFor<T>,
+            max_attendees: Option<u32>,
+            oracle_threshold: u32,
+            budget: Option<ChallengeBudget<BalanceOf<T>>>,
+        ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             // Create a challenge
-            ensure!(Self::valid_geohash(&challenge), Error::<T>::InvalidGeohash);
+            for cell in core::iter::once(&geohash).chain(cells.iter()) {
+                ensure!(Self::valid_geohash(cell), Error::<T>::InvalidGeohash);
+                ensure!(
+                    (T::MinChallengePrecision::get()..=T::MaxChallengePrecision::get())
+                        .contains(&(cell.len() as u32)),
+                    Error::<T>::InvalidPrecision
+                );
+            }
+            if let Tolerance::Metres(metres) = tolerance {
+                ensure!(
+                    metres <= T::MaxToleranceMetres::get(),
+                    Error::<T>::InvalidTolerance
+                );
+            }
             ensure!(
-                Challenges::<T>::contains_key(&challenge) == false,
-                Error::<T>::InvalidGeohash
+                (1..=T::MaxAttestations::get()).contains(&oracle_threshold),
+                Error::<T>::InvalidThreshold
+            );
+            ensure!(
+                opens_at < closes_at && frame_system::Pallet::<T>::block_number() < closes_at,
+                Error::<T>::InvalidWindow
             );
-            // Store the validated geohash
-            Challenges::<T>::insert(challenge.clone(), true);
 
-            Self::deposit_event(Event::ChallengeCreated { who, challenge });
+            let challenge = NextChallengeId::<T>::get();
+            NextChallengeId::<T>::put(challenge.checked_add(1).ok_or(ArithmeticError::Overflow)?);
+
+            // Schedule the challenge to close at the end of its window, or in the next block with
+            // room if that one is full. Submissions are only accepted before `closes_at` either
+            // way, so filling a block can't keep other organizers from closing challenges then.
+            let expires_at = (0..=EXPIRY_SPILLOVER_BLOCKS)
+                .map(|offset| closes_at.saturating_add(offset.into()))
+                .find(|block| {
+                    ChallengeExpiries::<T>::decode_len(block).unwrap_or(0)
+                        < T::MaxChallengesPerBlock::get() as usize
+                })
+                .ok_or(Error::<T>::TooManyExpiries)?;
+            ChallengeExpiries::<T>::try_mutate(expires_at, |expiring| expiring.try_push(challenge))
+                .map_err(|_| Error::<T>::TooManyExpiries)?;
+
+            T::Mint::create_collection(challenge, &who)?;
+            let escrow = budget
+                .map(|budget| Self::fund_escrow(&who, budget, max_attendees))
+                .transpose()?;
+
+            // Store the validated geohash, holding a deposit for its storage
+            let mut info = ChallengeInfo {
+                organizer: who.clone(),
+                deposit: Zero::zero(),
+                geohash: geohash.clone(),
+                tolerance,
+                opens_at,
+                closes_at,
+                max_attendees,
+                attendees: 0,
+                oracle_threshold,
+                circuit: ActiveCircuit::<T>::get(),
+                status: ChallengeStatus::Open,
+            };
+            info.deposit = Self::challenge_deposit(&info, &cells);
+            if !info.deposit.is_zero() {
+                T::Currency::hold(&HoldReason::ChallengeDeposit.into(), &who, info.deposit)?;
+            }
+            Challenges::<T>::insert(challenge, info);
+            ChallengesByArea::<T>::insert(Self::area(&geohash), challenge, &geohash);
+            if !cells.is_empty() {
+                ChallengeCells::<T>::insert(challenge, cells);
+            }
+
+            Self::deposit_event(Event::ChallengeCreated {
+                who: who.clone(),
+                challenge,
+                geohash,
+                opens_at,
+                closes_at,
+            });
+            if let Some(escrow) = escrow {
+                Self::deposit_event(Event::BudgetFunded {
+                    challenge,
+                    organizer: who,
+                    amount: escrow.remaining,
+                });
+                Escrows::<T>::insert(challenge, escrow);
+            }
             Ok(())
         }
 
         #[pallet::call_index(1)]
-        #[pallet::weight(0)]
+        #[pallet::weight(
+            T::WeightInfo::submission_with_signature(attestations.len() as u32, location.len() as u32)
+                .saturating_add(T::Mint::mint_weight())
+        )]
         pub fn submission_with_signature(
             origin: OriginFor<T>,
-            challenge: Challenge<T>,
-            location: Challenge<T>,
-            signature: RawSignature,
+            challenge: ChallengeId,
+            location: Geohash<T>,
+            expires_at: BlockNumberFor<T>,
+            nonce: u64,
+            attestations: Attestations<T>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            ensure!(
-                !Submissions::<T>::contains_key(&challenge, &who),
-                Error::<T>::AlreadySubmitted
-            );
-            ensure!(
-                Self::geohash_in_geohash(&location, &challenge),
-                Error::<T>::InvalidGeohash
-            );
+            let info = Self::validate_signature_submission(
+                &who,
+                challenge,
+                &location,
+                expires_at,
+                nonce,
+                &attestations,
+            )?;
+            Self::accept_signature_submission(who, challenge, info, nonce, attestations)
+        }
 
-            let message = T::PayloadHasher::hash(&location);
-            let public_key = Oracle::<T>::get().expect("oracle key");
-            let public_key = T::PublicKeyOfOracle::from_slice(&public_key)
-                .map_err(|_| Error::<T>::InvalidPublicKey)?;
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::add_oracle(metadata.len() as u32))]
+        pub fn add_oracle(
+            origin: OriginFor<T>,
+            oracle: RawPublicKey,
+            metadata: BoundedVec<u8, T::MaxOracleMetadataLength>,
+        ) -> DispatchResult {
+            T::OracleOrigin::ensure_origin(origin)?;
+            Self::register_oracle(&oracle, oracle.clone(), metadata)?;
 
-            ensure!(
-                T::Verify::verify(
-                    &T::Signature::from_slice(&signature)
-                        .map_err(|_| Error::<T>::InvalidSignature)?,
-                    message,
-                    &public_key
-                ),
-                Error::<T>::InvalidSignature
-            );
+            Self::deposit_event(Event::OracleAdded { oracle });
+            Ok(())
+        }
 
-            T::Mint::mint(&who);
-            Submissions::<T>::insert(challenge.clone(), who.clone(), true);
+        #[pallet::call_index(3)]
+        // The geohash of the challenge isn't known until it is read, so assume the longest
+        #[pallet::weight(
+            T::WeightInfo::submission_with_proof(
+                T::MaxChallengePrecision::get(),
+                Pallet::<T>::is_compressed(proof) as u32,
+            )
+            .saturating_add(T::Mint::mint_weight())
+        )]
+        pub fn submission_with_proof(
+            origin: OriginFor<T>,
+            challenge: ChallengeId,
+            proof: RawProof<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let (mut info, nullifier) = Self::validate_proof_submission(&who, challenge, &proof)?;
 
-            Self::deposit_event(Event::SubmissionAccepted {
+            T::Mint::mint(&who, challenge, info.attendees, &info.geohash)?;
+            Self::pay_reward(challenge, &info.organizer, &who)?;
+            ProofNullifiers::<T>::insert(challenge, nullifier, ());
+            info.attendees.saturating_inc();
+            Challenges::<T>::insert(challenge, info);
+            Submissions::<T>::insert(challenge, who.clone(), true);
+            AttendedChallenges::<T>::insert(&who, challenge, ());
+
+            Self::deposit_event(Event::ProofSubmissionAccepted {
                 who,
                 challenge,
-                signature,
+                nullifier,
             });
+            Ok(())
+        }
 
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::close_challenge())]
+        pub fn close_challenge(origin: OriginFor<T>, challenge: ChallengeId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::end_challenge(&who, challenge, ChallengeStatus::Closed)?;
+
+            Self::deposit_event(Event::ChallengeClosed { challenge });
             Ok(())
         }
 
-        #[pallet::call_index(2)]
-        #[pallet::weight(0)]
-        pub fn set_oracle_public_key(
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::cancel_challenge())]
+        pub fn cancel_challenge(origin: OriginFor<T>, challenge: ChallengeId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::end_challenge(&who, challenge, ChallengeStatus::Cancelled)?;
+
+            Self::deposit_event(Event::ChallengeCancelled { challenge });
+            Ok(())
+        }
+
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::remove_oracle())]
+        pub fn remove_oracle(origin: OriginFor<T>, oracle: RawPublicKey) -> DispatchResult {
+            T::OracleOrigin::ensure_origin(origin)?;
+            ensure!(Oracles::<T>::contains_key(&oracle), Error::<T>::NoOracle);
+            Oracles::<T>::remove(&oracle);
+
+            Self::deposit_event(Event::OracleRemoved { oracle });
+            Ok(())
+        }
+
+        /// Replace `old` with `new`, accepting both keys for `OracleRotationPeriod` blocks.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::rotate_oracle())]
+        pub fn rotate_oracle(
             origin: OriginFor<T>,
-            public_key: RawPublicKey,
+            old: RawPublicKey,
+            new: RawPublicKey,
         ) -> DispatchResult {
-            ensure_root(origin)?;
-            Oracle::<T>::put(public_key);
+            T::OracleOrigin::ensure_origin(origin)?;
+            let retires_at = frame_system::Pallet::<T>::block_number()
+                .saturating_add(T::OracleRotationPeriod::get());
+            let (operator, metadata) = Oracles::<T>::try_mutate(&old, |info| {
+                let info = info
+                    .as_mut()
+                    .filter(|_| Self::oracle_is_active(&old))
+                    .ok_or(Error::<T>::NoOracle)?;
+                info.retires_at = Some(retires_at);
+                Ok::<_, Error<T>>((info.operator.clone(), info.metadata.clone()))
+            })?;
+            Self::register_oracle(&new, operator, metadata)?;
+
+            Self::deposit_event(Event::OracleRotated {
+                old,
+                new,
+                retires_at,
+            });
             Ok(())
         }
 
-        #[pallet::call_index(3)]
-        #[pallet::weight(0)]
-        pub fn submission_with_proof(
+        /// Register the verifying key for a new `circuit` and make it the active circuit.
+        ///
+        /// Existing challenges keep verifying proofs against the circuit they were created with.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_proof_verifying_key(key.len() as u32))]
+        pub fn set_proof_verifying_key(
             origin: OriginFor<T>,
-            challenge: Challenge<T>,
-            proof: RawProof,
+            circuit: CircuitId,
+            key: RawVerifyingKey<T>,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
+            T::VerifyingKeyOrigin::ensure_origin(origin)?;
             ensure!(
-                Self::verify_zkp(&proof, &challenge),
-                Error::<T>::InvalidProof
+                !VerifyingKeys::<T>::contains_key(circuit),
+                Error::<T>::CircuitAlreadyRegistered
             );
-            T::Mint::mint(&who);
-            Submissions::<T>::insert(challenge.clone(), who.clone(), true);
+            VerifyingKeys::<T>::insert(circuit, &key);
+            ActiveCircuit::<T>::put(circuit);
 
+            Self::deposit_event(Event::VerifyingKeySet { circuit, key });
             Ok(())
         }
+
+        /// Release what is left of a finished challenge's budget back to its organizer.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::refund_budget())]
+        pub fn refund_budget(origin: OriginFor<T>, challenge: ChallengeId) -> DispatchResult {
+            ensure_signed(origin)?;
+            let info = Challenges::<T>::get(challenge).ok_or(Error::<T>::UnknownChallenge)?;
+            ensure!(
+                info.status != ChallengeStatus::Open,
+                Error::<T>::ChallengeStillOpen
+            );
+            Self::refund_escrow(challenge, &info.organizer)
+        }
+
+        /// Clear up to `limit` submissions, and the nonces and proof nullifiers they used, of a
+        /// finished challenge.
+        ///
+        /// Anyone can reap a challenge. Once its last entry is cleared the challenge is
+        /// removed, refunding what is left of its budget and reward pot and returning the
+        /// organizer's deposit.
+        #[pallet::call_index(10)]
+        #[pallet::weight(
+            T::WeightInfo::reap_challenge(*limit).saturating_add(T::Mint::reap_collection_weight())
+        )]
+        pub fn reap_challenge(
+            origin: OriginFor<T>,
+            challenge: ChallengeId,
+            limit: u32,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let info = Challenges::<T>::get(challenge).ok_or(Error::<T>::UnknownChallenge)?;
+            ensure!(
+                info.status != ChallengeStatus::Open,
+                Error::<T>::ChallengeStillOpen
+            );
+
+            let (removed, left_off) = Self::clear_challenge_storage(challenge, limit);
+            Self::deposit_event(Event::SubmissionsReaped { challenge, removed });
+            if let Some(left_off) = left_off {
+                ReapCursors::<T>::insert(challenge, left_off);
+                return Ok(());
+            }
+
+            if Escrows::<T>::contains_key(challenge) {
+                Self::refund_escrow(challenge, &info.organizer)?;
+            }
+            T::Mint::reap_collection(challenge, &info.organizer)?;
+            let deposit = if info.deposit.is_zero() {
+                info.deposit
+            } else {
+                T::Currency::release(
+                    &HoldReason::ChallengeDeposit.into(),
+                    &info.organizer,
+                    info.deposit,
+                    Precision::BestEffort,
+                )?
+            };
+            Challenges::<T>::remove(challenge);
+            ChallengesByArea::<T>::remove(Self::area(&info.geohash), challenge);
+            ChallengeCells::<T>::remove(challenge);
+
+            Self::deposit_event(Event::ChallengeReaped {
+                challenge,
+                organizer: info.organizer,
+                deposit,
+            });
+            Ok(())
+        }
+
+        /// Submits attendance on behalf of `attendee` without a signed origin, so attendees
+        /// don't need a balance to pay fees. The oracle attestations, which are signed over
+        /// `attendee`, authorise the call and are checked by `validate_unsigned` before it enters
+        /// the transaction pool.
+        #[pallet::call_index(11)]
+        #[pallet::weight(
+            T::WeightInfo::unsigned_submission_with_signature(
+                attestations.len() as u32,
+                location.len() as u32,
+            )
+            .saturating_add(T::Mint::mint_weight())
+        )]
+        pub fn unsigned_submission_with_signature(
+            origin: OriginFor<T>,
+            attendee: T::AccountId,
+            challenge: ChallengeId,
+            location: Geohash<T>,
+            expires_at: BlockNumberFor<T>,
+            nonce: u64,
+            attestations: Attestations<T>,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            let info = Self::validate_signature_submission(
+                &attendee,
+                challenge,
+                &location,
+                expires_at,
+                nonce,
+                &attestations,
+            )?;
+            Self::accept_signature_submission(attendee, challenge, info, nonce, attestations)
+        }
     }
 
     use ark_bn254::Bn254;
     use ark_bn254::Fr;
+    use ark_ff::PrimeField;
     use ark_groth16::Groth16;
     use ark_groth16::{Proof, VerifyingKey};
-    use ark_serialize::CanonicalDeserialize;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
     use ark_snark::SNARK;
 
     impl<T: Config> Pallet<T> {
-        pub fn valid_geohash(geohash: &Challenge<T>) -> bool {
-            geohash
-                .iter()
-                .all(|c| "0123456789bcdefghjkmnpqrstuvwxyz".contains(*c as char))
+        pub fn valid_geohash(geohash: &Geohash<T>) -> bool {
+            geohash_utils::is_valid(geohash)
+        }
+
+        /// Whether signatures from `oracle` are currently accepted.
+        pub fn oracle_is_active(oracle: &RawPublicKey) -> bool {
+            Self::active_oracle(oracle).is_some()
+        }
+
+        /// The details of `oracle`, if its signatures are currently accepted.
+        fn active_oracle(oracle: &RawPublicKey) -> Option<OracleInfoOf<T>> {
+            let now = frame_system::Pallet::<T>::block_number();
+            Oracles::<T>::get(oracle)
+                .filter(|info| info.retires_at.is_none_or(|retires_at| now < retires_at))
+        }
+
+        /// The `page`th page of challenges, by id.
+        ///
+        /// Pages cover `CHALLENGES_PAGE_SIZE` ids each, so they have fewer entries once
+        /// challenges are reaped.
+        pub fn challenges(page: u32) -> Vec<(ChallengeId, ChallengeInfoOf<T>)> {
+            let first = page.saturating_mul(CHALLENGES_PAGE_SIZE);
+            let last = first
+                .saturating_add(CHALLENGES_PAGE_SIZE)
+                .min(NextChallengeId::<T>::get());
+            (first..last)
+                .filter_map(|challenge| {
+                    Challenges::<T>::get(challenge).map(|info| (challenge, info))
+                })
+                .collect()
+        }
+
+        /// The `page`th page of challenges whose geohash starts with `prefix`.
+        ///
+        /// Only the challenges in the area `prefix` falls within are read, so `prefix` must be at
+        /// least `MinChallengePrecision` characters long, and nothing is returned otherwise.
+        pub fn challenges_for_geohash(
+            prefix: &[u8],
+            page: u32,
+        ) -> Vec<(ChallengeId, ChallengeInfoOf<T>)> {
+            let Some(area) = prefix
+                .get(..T::MinChallengePrecision::get() as usize)
+                .and_then(|area| Geohash::<T>::try_from(area.to_vec()).ok())
+            else {
+                return Vec::new();
+            };
+            ChallengesByArea::<T>::iter_prefix(area)
+                .filter(|(_, geohash)| geohash.starts_with(prefix))
+                .skip((page as usize).saturating_mul(CHALLENGES_PAGE_SIZE as usize))
+                .take(CHALLENGES_PAGE_SIZE as usize)
+                .filter_map(|(challenge, _)| {
+                    Challenges::<T>::get(challenge).map(|info| (challenge, info))
+                })
+                .collect()
+        }
+
+        /// Whether `account` has an accepted submission for `challenge`.
+        pub fn has_attended(challenge: ChallengeId, account: &T::AccountId) -> bool {
+            Submissions::<T>::contains_key(challenge, account)
+        }
+
+        /// The `page`th page of accounts with an accepted submission for `challenge`.
+        pub fn attendees(challenge: ChallengeId, page: u32) -> Vec<T::AccountId> {
+            Submissions::<T>::iter_key_prefix(challenge)
+                .skip((page as usize).saturating_mul(ATTENDEES_PAGE_SIZE as usize))
+                .take(ATTENDEES_PAGE_SIZE as usize)
+                .collect()
+        }
+
+        /// The `page`th page of challenges `account` has an accepted submission for.
+        pub fn attended(
+            account: &T::AccountId,
+            page: u32,
+        ) -> Vec<(ChallengeId, ChallengeInfoOf<T>)> {
+            AttendedChallenges::<T>::iter_key_prefix(account)
+                .skip((page as usize).saturating_mul(ATTENDEES_PAGE_SIZE as usize))
+                .take(ATTENDEES_PAGE_SIZE as usize)
+                .filter_map(|challenge| {
+                    Challenges::<T>::get(challenge).map(|info| (challenge, info))
+                })
+                .collect()
+        }
+
+        /// The public keys of the oracles whose signatures are currently accepted.
+        pub fn oracle_keys() -> Vec<Vec<u8>> {
+            Oracles::<T>::iter_keys()
+                .filter(Self::oracle_is_active)
+                .map(BoundedVec::into_inner)
+                .collect()
+        }
+
+        /// The active circuit and its verifying key.
+        pub fn verifying_key() -> Option<(CircuitId, Vec<u8>)> {
+            let circuit = ActiveCircuit::<T>::get()?;
+            VerifyingKeys::<T>::get(circuit).map(|key| (circuit, key.into_inner()))
+        }
+
+        fn register_oracle(
+            oracle: &RawPublicKey,
+            operator: RawPublicKey,
+            metadata: BoundedVec<u8, T::MaxOracleMetadataLength>,
+        ) -> DispatchResult {
+            T::PublicKeyOfOracle::from_slice(oracle).map_err(|_| Error::<T>::InvalidPublicKey)?;
+            ensure!(
+                !Oracles::<T>::contains_key(oracle),
+                Error::<T>::OracleAlreadyRegistered
+            );
+            Oracles::<T>::insert(
+                oracle,
+                OracleInfo {
+                    metadata,
+                    operator,
+                    activated_at: frame_system::Pallet::<T>::block_number(),
+                    retires_at: None,
+                },
+            );
+            Ok(())
+        }
+
+        /// Checks every attestation is a valid signature of `message` by a distinct active oracle.
+        ///
+        /// The old and new keys of a rotated oracle belong to the same operator, so only one
+        /// of them counts towards the threshold.
+        fn verify_attestations(
+            attestations: &Attestations<T>,
+            message: T::Hash,
+        ) -> Result<(), Error<T>> {
+            let mut operators = Vec::with_capacity(attestations.len());
+            for (oracle, signature) in attestations.iter() {
+                let operator = Self::active_oracle(oracle)
+                    .ok_or(Error::<T>::NoOracle)?
+                    .operator;
+                ensure!(!operators.contains(&operator), Error::<T>::DuplicateOracle);
+                operators.push(operator);
+                let public_key = T::PublicKeyOfOracle::from_slice(oracle)
+                    .map_err(|_| Error::<T>::InvalidPublicKey)?;
+                let signature = T::Signature::from_slice(signature)
+                    .map_err(|_| Error::<T>::InvalidSignature)?;
+                ensure!(
+                    T::Verify::verify(&signature, message, &public_key),
+                    Error::<T>::InvalidSignature
+                );
+            }
+            Ok(())
+        }
+
+        /// Dry-runs a submission `call` from `who`, dispatching it and rolling back every change.
+        ///
+        /// Failures of the mint or reward a submission triggers are reported as well as failures
+        /// of its checks.
+        pub fn validate_submission(
+            who: &T::AccountId,
+            call: &Call<T>,
+        ) -> Result<(), SubmissionError<T>> {
+            let origin = match call {
+                Call::submission_with_signature { .. } | Call::submission_with_proof { .. } => {
+                    frame_system::RawOrigin::Signed(who.clone())
+                }
+                Call::unsigned_submission_with_signature { .. } => frame_system::RawOrigin::None,
+                _ => return Err(Error::<T>::NotASubmission.into()),
+            };
+            let result = with_transaction(|| {
+                let result = call.clone().dispatch_bypass_filter(origin.into());
+                TransactionOutcome::Rollback(Ok::<_, DispatchError>(result))
+            })
+            .map_err(SubmissionError::Dispatch)?;
+            result.map(|_| ()).map_err(|e| e.error.into())
+        }
+
+        /// Checks a submission to `challenge` from `who` attested by oracle signatures, returning
+        /// the challenge.
+        fn validate_signature_submission(
+            who: &T::AccountId,
+            challenge: ChallengeId,
+            location: &Geohash<T>,
+            expires_at: BlockNumberFor<T>,
+            nonce: u64,
+            attestations: &Attestations<T>,
+        ) -> Result<ChallengeInfoOf<T>, Error<T>> {
+            let info = Self::open_challenge(challenge)?;
+            ensure!(
+                !Submissions::<T>::contains_key(challenge, who),
+                Error::<T>::AlreadySubmitted
+            );
+            ensure!(
+                Self::location_in_challenge(challenge, &info, location),
+                Error::<T>::InvalidGeohash
+            );
+            ensure!(
+                frame_system::Pallet::<T>::block_number() <= expires_at,
+                Error::<T>::AttestationExpired
+            );
+            ensure!(
+                !UsedNonces::<T>::contains_key(challenge, (who, nonce)),
+                Error::<T>::NonceAlreadyUsed
+            );
+
+            let payload = AttestationPayload {
+                account: who.clone(),
+                challenge,
+                location: location.clone(),
+                expires_at,
+                nonce,
+                genesis_hash: Self::genesis_hash(),
+            };
+            let message = T::PayloadHasher::hash(&payload.encode());
+            Self::verify_attestations(attestations, message)?;
+            ensure!(
+                attestations.len() as u32 >= info.oracle_threshold,
+                Error::<T>::ThresholdNotMet
+            );
+            Ok(info)
+        }
+
+        /// Mints the badge, pays the reward and records a submission which has passed
+        /// [`Self::validate_signature_submission`].
+        fn accept_signature_submission(
+            who: T::AccountId,
+            challenge: ChallengeId,
+            mut info: ChallengeInfoOf<T>,
+            nonce: u64,
+            attestations: Attestations<T>,
+        ) -> DispatchResult {
+            T::Mint::mint(&who, challenge, info.attendees, &info.geohash)?;
+            Self::pay_reward(challenge, &info.organizer, &who)?;
+            UsedNonces::<T>::insert(challenge, (&who, nonce), ());
+            info.attendees.saturating_inc();
+            Challenges::<T>::insert(challenge, info);
+            Submissions::<T>::insert(challenge, who.clone(), true);
+            AttendedChallenges::<T>::insert(&who, challenge, ());
+
+            Self::deposit_event(Event::SubmissionAccepted {
+                who,
+                challenge,
+                attestations,
+            });
+            Ok(())
+        }
+
+        /// Checks a submission to `challenge` from `who` with a proof of location, returning the
+        /// challenge and the proof's nullifier.
+        fn validate_proof_submission(
+            who: &T::AccountId,
+            challenge: ChallengeId,
+            proof: &RawProof<T>,
+        ) -> Result<(ChallengeInfoOf<T>, T::Hash), Error<T>> {
+            let info = Self::open_challenge(challenge)?;
+            ensure!(
+                !Submissions::<T>::contains_key(challenge, who),
+                Error::<T>::AlreadySubmitted
+            );
+            let binding = Self::proof_binding(challenge, who);
+            let nullifier = Self::verify_zkp(proof, info.circuit, &info.geohash, binding)?;
+            ensure!(
+                !ProofNullifiers::<T>::contains_key(challenge, nullifier),
+                Error::<T>::ProofAlreadyUsed
+            );
+            Ok((info, nullifier))
+        }
+
+        /// Returns the challenge if it is currently accepting submissions.
+        fn open_challenge(challenge: ChallengeId) -> Result<ChallengeInfoOf<T>, Error<T>> {
+            let info = Challenges::<T>::get(challenge).ok_or(Error::<T>::UnknownChallenge)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(
+                info.status == ChallengeStatus::Open
+                    && info.opens_at <= now
+                    && now < info.closes_at,
+                Error::<T>::ChallengeNotOpen
+            );
+            ensure!(
+                info.max_attendees.is_none_or(|max| info.attendees < max),
+                Error::<T>::ChallengeFull
+            );
+            Ok(info)
+        }
+
+        /// Holds `budget` from `organizer`, working out how much each attendee is paid.
+        fn fund_escrow(
+            organizer: &T::AccountId,
+            budget: ChallengeBudget<BalanceOf<T>>,
+            max_attendees: Option<u32>,
+        ) -> Result<Escrow<BalanceOf<T>>, DispatchError> {
+            let per_attendee = match budget.payout {
+                Payout::Equal => {
+                    let attendees = max_attendees.ok_or(Error::<T>::UnboundedAttendees)?;
+                    budget
+                        .amount
+                        .checked_div(&attendees.into())
+                        .ok_or(Error::<T>::InvalidBudget)?
+                }
+                Payout::FirstCome(amount) => amount,
+            };
+            // Each share must be able to create the attendee's account
+            ensure!(
+                per_attendee >= T::Currency::minimum_balance() && per_attendee <= budget.amount,
+                Error::<T>::InvalidBudget
+            );
+            T::Currency::hold(
+                &HoldReason::ChallengeBudget.into(),
+                organizer,
+                budget.amount,
+            )?;
+            Ok(Escrow {
+                remaining: budget.amount,
+                per_attendee,
+            })
         }
 
-        fn geohash_in_geohash(geohash: &Challenge<T>, challenge: &Challenge<T>) -> bool {
-            geohash.starts_with(challenge)
+        /// Clears up to `limit` of the submissions, nonces and nullifiers of `challenge`,
+        /// carrying on from where the last call left off.
+        ///
+        /// Returns the number of entries removed, and where to carry on from if any are left.
+        fn clear_challenge_storage(
+            challenge: ChallengeId,
+            limit: u32,
+        ) -> (u32, Option<(ReapStage, Option<ReapCursor>)>) {
+            let (mut stage, mut cursor) =
+                ReapCursors::<T>::take(challenge).unwrap_or((ReapStage::Submissions, None));
+            let mut removed = 0;
+            let mut remaining = limit;
+            loop {
+                // Once the limit is reached this only checks whether the stage is empty
+                let cursor_bytes = cursor.as_ref().map(|c| c.as_slice());
+                let (unique, backend, maybe_cursor) = match stage {
+                    // Each attendee's index entry goes with their submission, so submissions are
+                    // drained rather than cleared, and need no cursor
+                    ReapStage::Submissions => {
+                        let mut drained = 0;
+                        for (who, _) in
+                            Submissions::<T>::drain_prefix(challenge).take(remaining as usize)
+                        {
+                            AttendedChallenges::<T>::remove(&who, challenge);
+                            drained += 1;
+                        }
+                        let left = Submissions::<T>::contains_prefix(challenge);
+                        (drained, drained, left.then(Vec::new))
+                    }
+                    ReapStage::UsedNonces => {
+                        let result =
+                            UsedNonces::<T>::clear_prefix(challenge, remaining, cursor_bytes);
+                        (result.unique, result.backend, result.maybe_cursor)
+                    }
+                    ReapStage::ProofNullifiers => {
+                        let result =
+                            ProofNullifiers::<T>::clear_prefix(challenge, remaining, cursor_bytes);
+                        (result.unique, result.backend, result.maybe_cursor)
+                    }
+                };
+                removed.saturating_accrue(unique);
+                remaining.saturating_reduce(backend);
+                if let Some(next) = maybe_cursor {
+                    // A cursor too long to store is dropped, the next call starts from the first
+                    // entry left
+                    return (removed, Some((stage, ReapCursor::try_from(next).ok())));
+                }
+                cursor = None;
+                stage = match stage {
+                    ReapStage::Submissions => ReapStage::UsedNonces,
+                    ReapStage::UsedNonces => ReapStage::ProofNullifiers,
+                    ReapStage::ProofNullifiers => return (removed, None),
+                };
+            }
+        }
+
+        /// Releases what is left of the challenge's budget back to `organizer`.
+        fn refund_escrow(challenge: ChallengeId, organizer: &T::AccountId) -> DispatchResult {
+            let escrow = Escrows::<T>::take(challenge).ok_or(Error::<T>::NoBudget)?;
+            let amount = T::Currency::release(
+                &HoldReason::ChallengeBudget.into(),
+                organizer,
+                escrow.remaining,
+                Precision::BestEffort,
+            )?;
+
+            Self::deposit_event(Event::BudgetRefunded {
+                challenge,
+                organizer: organizer.clone(),
+                amount,
+            });
+            Ok(())
         }
 
-        fn verify_zkp(proof: &RawProof, challenge: &Challenge<T>) -> bool {
-            let proof = Proof::<Bn254>::deserialize_uncompressed(proof.as_slice()).expect("proof");
-            let verifying_key_bytes = ProofVerifyingKey::<T>::get().expect("verifying key");
+        /// The deposit held for storing `info` and the extra `cells` of its area, charged per
+        /// byte of their encoding.
+        pub fn challenge_deposit(info: &ChallengeInfoOf<T>, cells: &[Geohash<T>]) -> BalanceOf<T> {
+            let cells = if cells.is_empty() {
+                0
+            } else {
+                cells.encoded_size()
+            };
+            let bytes = (info.encoded_size() + cells) as u32;
+            T::DepositPerByte::get()
+                .saturating_mul(bytes.into())
+                .saturating_add(T::ChallengeDepositBase::get())
+        }
 
-            let verifying_key =
-                VerifyingKey::deserialize_uncompressed(verifying_key_bytes.as_slice())
-                    .expect("verifying key");
+        /// Pays `who` their share of the challenge's budget, if it has one.
+        fn pay_reward(
+            challenge: ChallengeId,
+            organizer: &T::AccountId,
+            who: &T::AccountId,
+        ) -> DispatchResult {
+            let Some(mut escrow) = Escrows::<T>::get(challenge) else {
+                return Ok(());
+            };
+            let amount = escrow.per_attendee.min(escrow.remaining);
+            // The last of a first come budget may be too little to pay, and is refunded instead
+            if amount.is_zero() || amount < T::Currency::minimum_balance() {
+                return Ok(());
+            }
+            T::Currency::transfer_on_hold(
+                &HoldReason::ChallengeBudget.into(),
+                organizer,
+                who,
+                amount,
+                Precision::Exact,
+                Restriction::Free,
+                Fortitude::Polite,
+            )?;
+            escrow.remaining.saturating_reduce(amount);
+            Escrows::<T>::insert(challenge, escrow);
+
+            Self::deposit_event(Event::RewardPaid {
+                challenge,
+                who: who.clone(),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Moves an open challenge into a final `status` on behalf of its organizer.
+        fn end_challenge(
+            who: &T::AccountId,
+            challenge: ChallengeId,
+            status: ChallengeStatus,
+        ) -> DispatchResult {
+            Challenges::<T>::try_mutate(challenge, |info| {
+                let info = info.as_mut().ok_or(Error::<T>::UnknownChallenge)?;
+                ensure!(&info.organizer == who, Error::<T>::NotOrganizer);
+                ensure!(
+                    info.status == ChallengeStatus::Open,
+                    Error::<T>::ChallengeNotOpen
+                );
+                info.status = status;
+                Ok(())
+            })
+        }
 
-            let public_input: sp_runtime::Vec<Fr> =
+        /// The account holding the reward pot of `challenge`.
+        ///
+        /// Anyone can top the pot up, and whatever is left when the challenge is reaped goes to
+        /// its organizer.
+        pub fn pot_account(challenge: ChallengeId) -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating(challenge)
+        }
+
+        /// The hash of the genesis block, which attestations commit to.
+        pub fn genesis_hash() -> T::Hash {
+            frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero())
+        }
+
+        /// Whether `location` is in the area of `challenge`, around its geohash or in one of its
+        /// extra cells.
+        fn location_in_challenge(
+            challenge: ChallengeId,
+            info: &ChallengeInfoOf<T>,
+            location: &Geohash<T>,
+        ) -> bool {
+            Self::geohash_in_area(location, &info.geohash, info.tolerance)
+                || ChallengeCells::<T>::get(challenge).is_some_and(|cells| {
+                    cells
+                        .iter()
+                        .any(|cell| geohash_utils::contains(cell, location))
+                })
+        }
+
+        fn geohash_in_area(
+            geohash: &Geohash<T>,
+            challenge: &Geohash<T>,
+            tolerance: Tolerance,
+        ) -> bool {
+            match tolerance {
+                Tolerance::Exact => geohash_utils::contains(challenge, geohash),
+                Tolerance::Neighbors => geohash_utils::within_cells(challenge, geohash, 1, 1),
+                Tolerance::Metres(metres) => {
+                    geohash_utils::within_distance(challenge, geohash, metres)
+                }
+            }
+        }
+
+        /// The value a proof for `challenge` submitted by `who` must be bound to.
+        ///
+        /// It's the circuit's last public input, so a proof, or a re-randomisation of it, can't
+        /// be submitted by another account.
+        pub fn proof_binding(challenge: ChallengeId, who: &T::AccountId) -> T::Hash {
+            T::Hashing::hash_of(&(challenge, who))
+        }
+
+        /// Verifies `proof` shows a location within `challenge` using the key for `circuit`, and
+        /// is bound to `binding`.
+        ///
+        /// Returns the proof's nullifier, the hash of its compressed serialization, which is the
+        /// same whichever serialization was submitted.
+        fn verify_zkp(
+            proof: &RawProof<T>,
+            circuit: Option<CircuitId>,
+            challenge: &Geohash<T>,
+            binding: T::Hash,
+        ) -> Result<T::Hash, Error<T>> {
+            let verifying_key_bytes = circuit
+                .and_then(VerifyingKeys::<T>::get)
+                .ok_or(Error::<T>::MissingVerifyingKey)?;
+            let proof =
+                Self::deserialize::<Proof<Bn254>>(proof).map_err(|_| Error::<T>::MalformedProof)?;
+            let verifying_key = Self::deserialize::<VerifyingKey<Bn254>>(&verifying_key_bytes)
+                .map_err(|_| Error::<T>::MalformedVerifyingKey)?;
+
+            let mut public_input: sp_runtime::Vec<Fr> =
                 challenge.iter().map(|c| (*c as u64).into()).collect();
+            public_input.push(Fr::from_le_bytes_mod_order(binding.as_ref()));
+
+            let verified = Groth16::<Bn254>::verify(&verifying_key, &public_input, &proof)
+                .map_err(|_| Error::<T>::PublicInputMismatch)?;
+            ensure!(verified, Error::<T>::InvalidProof);
+
+            let mut compressed = Vec::new();
+            proof
+                .serialize_compressed(&mut compressed)
+                .map_err(|_| Error::<T>::MalformedProof)?;
+            Ok(T::Hashing::hash(&compressed))
+        }
+
+        /// The area a challenge in `geohash` is indexed under in [`ChallengesByArea`].
+        fn area(geohash: &Geohash<T>) -> Geohash<T> {
+            let mut area = geohash.clone();
+            area.truncate(T::MinChallengePrecision::get() as usize);
+            area
+        }
+
+        /// Whether `proof` is too short to be uncompressed, so its points must be decompressed.
+        fn is_compressed(proof: &[u8]) -> bool {
+            proof.len() < Proof::<Bn254>::default().uncompressed_size()
+        }
 
-            Groth16::<Bn254>::verify(&verifying_key, &public_input, &proof).expect("verified")
+        /// Deserializes an uncompressed value, falling back to the compressed form.
+        fn deserialize<V: CanonicalDeserialize>(bytes: &[u8]) -> Result<V, SerializationError> {
+            V::deserialize_uncompressed(bytes).or_else(|_| V::deserialize_compressed(bytes))
         }
     }
 }
@@ -1,12 +1,9 @@
-use core::hash::Hasher as StdHasher;
-use core::marker::PhantomData;
-
-use crate::{self as pallet_attendance, Mintable};
-use codec::Encode;
-use frame_support::{derive_impl, parameter_types};
-use sp_core::crypto::Dummy;
-use sp_core::{Hasher, H256};
-use sp_runtime::BuildStorage;
+use crate::{self as pallet_attendance, rewards::FungibleReward, ChallengeId, Mintable};
+use core::cell::RefCell;
+use frame_support::{derive_impl, parameter_types, weights::Weight, PalletId};
+use frame_system::EnsureRoot;
+use sp_core::ed25519;
+use sp_runtime::{traits::BlakeTwo256, BuildStorage, DispatchError, DispatchResult};
 
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -15,6 +12,7 @@ frame_support::construct_runtime!(
     pub enum Test
     {
         System: frame_system,
+        Balances: pallet_balances,
         AttendanceModule: pallet_attendance,
     }
 );
@@ -22,58 +20,123 @@ frame_support::construct_runtime!(
 #[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
 impl frame_system::Config for Test {
     type Block = Block;
+    type AccountData = pallet_balances::AccountData<u64>;
 }
-use lazy_static::lazy_static;
-use std::collections::HashMap;
-use std::sync::Mutex;
 
-lazy_static! {
-    static ref MINTS: Mutex<HashMap<Vec<u8>, bool>> = Mutex::new(HashMap::new());
-}
-pub struct MockMinter<T>(PhantomData<T>);
-
-impl<T> Mintable<T> for MockMinter<T>
-where
-    T: Encode,
-{
-    fn mint(account: &T) {
-        let mut mints = MINTS.lock().unwrap();
-        mints.insert(account.encode(), true);
-    }
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+    type AccountStore = System;
+    type ExistentialDeposit = ExistentialDeposit;
 }
-parameter_types! {
-    pub const MaxGeohashLength: u32 = 12;
+
+thread_local! {
+    /// Badge collections created, by challenge
+    pub static COLLECTIONS: RefCell<Vec<(ChallengeId, u64)>> = const { RefCell::new(Vec::new()) };
+    /// Badges minted, as (account, challenge, attendee)
+    pub static MINTS: RefCell<Vec<(u64, ChallengeId, u32)>> = const { RefCell::new(Vec::new()) };
+    /// Whether minting fails
+    pub static FAIL_MINT: RefCell<bool> = const { RefCell::new(false) };
 }
 
-#[derive(Default)]
-pub struct StdDummyHasher;
-impl StdHasher for StdDummyHasher {
-    fn finish(&self) -> u64 {
-        0
+pub struct MockMinter;
+
+impl Mintable<u64> for MockMinter {
+    fn create_collection(challenge: ChallengeId, organizer: &u64) -> DispatchResult {
+        COLLECTIONS.with(|c| c.borrow_mut().push((challenge, *organizer)));
+        Ok(())
     }
 
-    fn write(&mut self, _bytes: &[u8]) {}
-}
+    fn mint(
+        account: &u64,
+        challenge: ChallengeId,
+        attendee: u32,
+        _geohash: &[u8],
+    ) -> DispatchResult {
+        if FAIL_MINT.with(|f| *f.borrow()) {
+            return Err(DispatchError::Other("mint failed"));
+        }
+        MINTS.with(|m| m.borrow_mut().push((*account, challenge, attendee)));
+        Ok(())
+    }
+
+    fn reap_collection(_challenge: ChallengeId, _organizer: &u64) -> DispatchResult {
+        Ok(())
+    }
 
-pub struct MockHasher;
-impl Hasher for MockHasher {
-    type Out = H256;
-    type StdHasher = StdDummyHasher;
-    const LENGTH: usize = 0;
-    fn hash(_x: &[u8]) -> Self::Out {
-        H256::default()
+    fn create_collection_weight() -> Weight {
+        Weight::zero()
+    }
+
+    fn mint_weight() -> Weight {
+        Weight::from_parts(1_000, 0)
+    }
+
+    fn reap_collection_weight() -> Weight {
+        Weight::zero()
     }
 }
+parameter_types! {
+    pub const MaxGeohashLength: u32 = 12;
+    pub const MinChallengePrecision: u32 = 2;
+    pub const MaxChallengePrecision: u32 = 8;
+    pub const MaxChallengeCells: u32 = 4;
+    pub const MaxToleranceMetres: u32 = 10_000;
+    pub const MaxChallengesPerBlock: u32 = 4;
+    pub const MaxOracleMetadataLength: u32 = 32;
+    pub const OracleRotationPeriod: u64 = 10;
+    pub const MaxAttestations: u32 = 3;
+    pub const MaxProofLength: u32 = 256;
+    pub const MaxVerifyingKeyLength: u32 = 2048;
+    pub const UnsignedPriority: u64 = 1_000;
+    pub const AttendancePalletId: PalletId = PalletId(*b"py/attnd");
+    pub const Reward: u64 = 10;
+    pub static ChallengeDepositBase: u64 = 0;
+    pub static DepositPerByte: u64 = 0;
+    pub static ExistentialDeposit: u64 = 1;
+}
 
 impl pallet_attendance::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type WeightInfo = ();
     type MaxGeohashLength = MaxGeohashLength;
-    type Mint = MockMinter<Self::AccountId>;
-    type PublicKeyOfOracle = Dummy;
-    type PayloadHasher = MockHasher;
-    type Signature = Dummy;
-    type Verify = Dummy;
+    type MinChallengePrecision = MinChallengePrecision;
+    type MaxChallengePrecision = MaxChallengePrecision;
+    type MaxChallengeCells = MaxChallengeCells;
+    type MaxToleranceMetres = MaxToleranceMetres;
+    type MaxChallengesPerBlock = MaxChallengesPerBlock;
+    type OracleOrigin = EnsureRoot<Self::AccountId>;
+    type MaxOracleMetadataLength = MaxOracleMetadataLength;
+    type OracleRotationPeriod = OracleRotationPeriod;
+    type MaxAttestations = MaxAttestations;
+    type VerifyingKeyOrigin = EnsureRoot<Self::AccountId>;
+    type MaxProofLength = MaxProofLength;
+    type MaxVerifyingKeyLength = MaxVerifyingKeyLength;
+    type UnsignedPriority = UnsignedPriority;
+    type Mint = (MockMinter, FungibleReward<Test, Balances, Reward>);
+    type PalletId = AttendancePalletId;
+    type Currency = Balances;
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type ChallengeDepositBase = ChallengeDepositBase;
+    type DepositPerByte = DepositPerByte;
+    type PublicKeyOfOracle = ed25519::Public;
+    type PayloadHasher = BlakeTwo256;
+    type Signature = ed25519::Signature;
+    type Verify = ed25519::Pair;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = MockOracles;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockOracles;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_attendance::BenchmarkHelper<ed25519::Public, ed25519::Signature> for MockOracles {
+    fn sign(index: u32, message: &[u8]) -> (ed25519::Public, ed25519::Signature) {
+        use sp_core::Pair;
+        let pair = ed25519::Pair::from_string(&format!("//Oracle{index}"), None)
+            .expect("valid derivation path");
+        (pair.public(), pair.sign(message))
+    }
 }
 
 // Build genesis storage according to the mock runtime.
@@ -0,0 +1,78 @@
+//! Implementations of [`Mintable`] which reward attendees.
+
+use crate::{ChallengeId, Config, Mintable, Pallet};
+use core::marker::PhantomData;
+use frame_support::{
+    pallet_prelude::*,
+    traits::{
+        fungible::{Inspect, Mutate},
+        tokens::{DepositConsequence, Fortitude, Preservation, Provenance},
+    },
+};
+use sp_runtime::traits::Zero;
+
+/// Pays each attendee `Reward` from the challenge's reward pot.
+///
+/// The pot is funded by transferring to [`Pallet::pot_account`], usually by the organizer though
+/// anyone can. Once the pot runs low attendees are paid whatever is left, and nothing once it is
+/// empty or what is left is too little to create the attendee's account. Whatever is still in
+/// the pot when the challenge is reaped is swept to the organizer.
+pub struct FungibleReward<T, Currency, Reward>(PhantomData<(T, Currency, Reward)>);
+
+impl<T, Currency, Reward> Mintable<T::AccountId> for FungibleReward<T, Currency, Reward>
+where
+    T: Config,
+    Currency: Mutate<T::AccountId>,
+    Reward: Get<Currency::Balance>,
+{
+    fn create_collection(_challenge: ChallengeId, _organizer: &T::AccountId) -> DispatchResult {
+        Ok(())
+    }
+
+    fn mint(
+        account: &T::AccountId,
+        challenge: ChallengeId,
+        _attendee: u32,
+        _geohash: &[u8],
+    ) -> DispatchResult {
+        let pot = Pallet::<T>::pot_account(challenge);
+        let available =
+            Currency::reducible_balance(&pot, Preservation::Expendable, Fortitude::Polite);
+        let amount = Reward::get().min(available);
+        // A reward below the existential deposit can't create the attendee's account, and
+        // shouldn't stop them attending
+        if !amount.is_zero()
+            && Currency::can_deposit(account, amount, Provenance::Extant)
+                == DepositConsequence::Success
+        {
+            Currency::transfer(&pot, account, amount, Preservation::Expendable)?;
+        }
+        Ok(())
+    }
+
+    fn reap_collection(challenge: ChallengeId, organizer: &T::AccountId) -> DispatchResult {
+        let pot = Pallet::<T>::pot_account(challenge);
+        let left = Currency::reducible_balance(&pot, Preservation::Expendable, Fortitude::Polite);
+        if !left.is_zero()
+            && Currency::can_deposit(organizer, left, Provenance::Extant)
+                == DepositConsequence::Success
+        {
+            Currency::transfer(&pot, organizer, left, Preservation::Expendable)?;
+        }
+        Ok(())
+    }
+
+    fn create_collection_weight() -> Weight {
+        Weight::zero()
+    }
+
+    fn mint_weight() -> Weight {
+        // Read the pot, write the pot and the attendee
+        T::DbWeight::get().reads_writes(2, 2)
+    }
+
+    fn reap_collection_weight() -> Weight {
+        // Read the pot, write the pot and the organizer
+        T::DbWeight::get().reads_writes(2, 2)
+    }
+}
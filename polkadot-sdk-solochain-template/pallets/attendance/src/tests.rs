@@ -1,10 +1,32 @@
 mod tests {
-    use crate::{mock::*, Challenges, Error};
-    use frame_support::{assert_noop, assert_ok};
-    use sp_core::{crypto::Dummy, Pair};
-    use sp_runtime::BoundedVec;
+    use crate::{
+        mock::*, ActiveCircuit, AttendedChallenges, AttestationPayload, ChallengeBudget,
+        ChallengeCells, ChallengeExpiries, ChallengeId, ChallengeStatus, Challenges, Error,
+        Escrows, Event, HoldReason, NextChallengeId, Oracles, Payout, ProofNullifiers, ReapCursors,
+        SubmissionError, Submissions, Tolerance, UsedNonces, VerifyingKeys,
+        EXPIRY_SPILLOVER_BLOCKS,
+    };
+    use ark_bn254::Bn254;
+    use ark_groth16::{Groth16, Proof, VerifyingKey};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use codec::Encode;
+    use frame_support::{
+        assert_noop, assert_ok,
+        traits::{fungible::InspectHold, ConstU32, Hooks},
+    };
+    use geohash_prover::{create_proof, setup_groth16, CompareCircuit};
+    use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+    use sp_core::{crypto::ByteArray, ed25519, Pair};
+    use sp_runtime::{
+        traits::BlakeTwo256,
+        traits::{Hash, ValidateUnsigned},
+        transaction_validity::{InvalidTransaction, TransactionSource, ValidTransaction},
+        BoundedVec, BuildStorage, DispatchError, DispatchResult, TokenError,
+    };
 
     const ALICE: u64 = 1;
+    const BOB: u64 = 2;
+    const CHARLIE: u64 = 3;
 
     #[derive(Clone)]
     struct Geohash(&'static str);
@@ -14,6 +36,87 @@ mod tests {
                 .expect("Failed to convert geohash string to bounded vector")
         }
     }
+
+    fn create(who: u64, geohash: &'static str) -> ChallengeId {
+        create_with_tolerance(who, geohash, Tolerance::Exact)
+    }
+
+    fn create_with_tolerance(who: u64, geohash: &'static str, tolerance: Tolerance) -> ChallengeId {
+        assert_ok!(AttendanceModule::create_challenge(
+            RuntimeOrigin::signed(who),
+            Geohash(geohash).into(),
+            tolerance,
+            Default::default(),
+            0,
+            100,
+            None,
+            1,
+            None
+        ));
+        NextChallengeId::<Test>::get() - 1
+    }
+
+    fn submit(who: u64, challenge: ChallengeId, location: &'static str, nonce: u64) {
+        assert_ok!(AttendanceModule::submission_with_signature(
+            RuntimeOrigin::signed(who),
+            challenge,
+            Geohash(location).into(),
+            100,
+            nonce,
+            attestations(vec![(
+                public(&oracle()),
+                attest(&oracle(), who, challenge, Geohash(location), 100, nonce)
+            )]),
+        ));
+    }
+
+    fn oracle() -> ed25519::Pair {
+        ed25519::Pair::from_seed(&[7u8; 32])
+    }
+
+    fn public(pair: &ed25519::Pair) -> BoundedVec<u8, ConstU32<32>> {
+        pair.public().to_raw_vec().try_into().expect("public key")
+    }
+
+    fn attestations(
+        entries: Vec<(BoundedVec<u8, ConstU32<32>>, BoundedVec<u8, ConstU32<64>>)>,
+    ) -> BoundedVec<(BoundedVec<u8, ConstU32<32>>, BoundedVec<u8, ConstU32<64>>), MaxAttestations>
+    {
+        entries.try_into().expect("attestations")
+    }
+
+    fn set_oracle(pair: &ed25519::Pair) {
+        assert_ok!(AttendanceModule::add_oracle(
+            RuntimeOrigin::root(),
+            public(pair),
+            Default::default()
+        ));
+    }
+
+    // Sign an attestation the same way the oracle does
+    fn attest(
+        pair: &ed25519::Pair,
+        account: u64,
+        challenge: ChallengeId,
+        location: Geohash,
+        expires_at: u64,
+        nonce: u64,
+    ) -> BoundedVec<u8, ConstU32<64>> {
+        let payload = AttestationPayload {
+            account,
+            challenge,
+            location: BoundedVec::<u8, MaxGeohashLength>::from(location),
+            expires_at,
+            nonce,
+            genesis_hash: System::block_hash(0),
+        };
+        let message = BlakeTwo256::hash(&payload.encode());
+        pair.sign(message.as_ref())
+            .to_raw_vec()
+            .try_into()
+            .expect("signature to vector")
+    }
+
     #[test]
     fn test_valid_geohash() {
         // Test valid geohashes
@@ -27,7 +130,7 @@ mod tests {
         }
 
         // Test invalid geohashes
-        let invalid_geohashes = ["abc", "!@#", "ABC"];
+        let invalid_geohashes = ["", "abc", "!@#", "ABC"];
         for geohash in invalid_geohashes {
             assert!(
                 !AttendanceModule::valid_geohash(&Geohash(geohash).into()),
@@ -41,16 +144,85 @@ mod tests {
     fn create_challenge() {
         new_test_ext().execute_with(|| {
             System::set_block_number(1);
-            let geohash = BoundedVec::<u8, MaxGeohashLength>::from(Geohash("bcd"));
             assert_ok!(AttendanceModule::create_challenge(
                 RuntimeOrigin::signed(ALICE),
-                geohash.clone()
+                Geohash("bcd").into(),
+                Tolerance::Exact,
+                Default::default(),
+                1,
+                10,
+                Some(2),
+                1,
+                None
             ));
-            assert!(Challenges::<Test>::contains_key(geohash.clone()));
+            let info = Challenges::<Test>::get(0).expect("challenge");
+            assert_eq!(info.organizer, ALICE);
+            assert_eq!(info.geohash, BoundedVec::from(Geohash("bcd")));
+            assert_eq!(info.status, ChallengeStatus::Open);
+            assert_eq!(NextChallengeId::<Test>::get(), 1);
+            assert_noop!(
+                AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(ALICE),
+                    Geohash("abc").into(),
+                    Tolerance::Exact,
+                    Default::default(),
+                    1,
+                    10,
+                    None,
+                    1,
+                    None
+                ),
+                Error::<Test>::InvalidGeohash
+            );
+
+            // Empty, world-spanning and overly precise challenges are rejected
             assert_noop!(
-                AttendanceModule::create_challenge(RuntimeOrigin::signed(ALICE), geohash),
+                AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(ALICE),
+                    Geohash("").into(),
+                    Tolerance::Exact,
+                    Default::default(),
+                    1,
+                    10,
+                    None,
+                    1,
+                    None
+                ),
                 Error::<Test>::InvalidGeohash
             );
+            for geohash in ["b", "bcdefghjk"] {
+                assert_noop!(
+                    AttendanceModule::create_challenge(
+                        RuntimeOrigin::signed(ALICE),
+                        Geohash(geohash).into(),
+                        Tolerance::Exact,
+                        Default::default(),
+                        1,
+                        10,
+                        None,
+                        1,
+                        None
+                    ),
+                    Error::<Test>::InvalidPrecision
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn geohash_can_host_multiple_challenges() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+
+            let first = create(ALICE, "u4pruyd");
+            let second = create(BOB, "u4pruyd");
+            assert_ne!(first, second);
+
+            submit(ALICE, first, "u4pruydqqv", 0);
+            submit(ALICE, second, "u4pruydqqv", 1);
+            assert!(Submissions::<Test>::contains_key(first, ALICE));
+            assert!(Submissions::<Test>::contains_key(second, ALICE));
         });
     }
 
@@ -58,63 +230,2103 @@ mod tests {
     fn submit_valid_geohash_for_challenge() {
         new_test_ext().execute_with(|| {
             System::set_block_number(1);
+            let oracle = oracle();
+            set_oracle(&oracle);
+
+            let challenge = create(ALICE, "bcd");
+
+            assert_ok!(AttendanceModule::submission_with_signature(
+                RuntimeOrigin::signed(ALICE),
+                challenge,
+                Geohash("bcdefg").into(),
+                10,
+                0,
+                attestations(vec![(
+                    public(&oracle()),
+                    attest(&oracle, ALICE, challenge, Geohash("bcdefg"), 10, 0)
+                )]),
+            ));
+            assert!(Submissions::<Test>::contains_key(challenge, ALICE));
+        });
+    }
+
+    // Submit `location` for `challenge` attested by the default oracle
+    fn submit_location(who: u64, challenge: ChallengeId, location: &'static str) -> DispatchResult {
+        AttendanceModule::submission_with_signature(
+            RuntimeOrigin::signed(who),
+            challenge,
+            Geohash(location).into(),
+            100,
+            0,
+            attestations(vec![(
+                public(&oracle()),
+                attest(&oracle(), who, challenge, Geohash(location), 100, 0),
+            )]),
+        )
+    }
+
+    #[test]
+    fn location_across_cell_edge_needs_neighbors_tolerance() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+
+            // "gcpuy" is the cell east of "gcpuv" and "gcpuz" is two cells east
+            let exact = create(ALICE, "gcpuv");
+            assert_noop!(
+                submit_location(ALICE, exact, "gcpuyxr1"),
+                Error::<Test>::InvalidGeohash
+            );
+
+            let neighbors = create_with_tolerance(ALICE, "gcpuv", Tolerance::Neighbors);
+            assert_ok!(submit_location(ALICE, neighbors, "gcpuvxr1"));
+            assert_ok!(submit_location(BOB, neighbors, "gcpuyxr1"));
+            assert_ok!(submit_location(CHARLIE, neighbors, "gcpuuxr1"));
+            assert_noop!(
+                submit_location(4, neighbors, "gcpuzz29"),
+                Error::<Test>::InvalidGeohash
+            );
+        });
+    }
+
+    #[test]
+    fn neighbors_tolerance_wraps_around_antimeridian() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+
+            // "xbpb" touches 180 degrees east, so "8000" on the other side is its east neighbor
+            let challenge = create_with_tolerance(ALICE, "xbpb", Tolerance::Neighbors);
+            assert_ok!(submit_location(ALICE, challenge, "800001z6"));
+            assert_ok!(submit_location(BOB, challenge, "xbp8pcb6"));
+            assert_noop!(
+                submit_location(CHARLIE, challenge, "800801v4"),
+                Error::<Test>::InvalidGeohash
+            );
+
+            let exact = create(ALICE, "xbpb");
+            assert_noop!(
+                submit_location(ALICE, exact, "800001z6"),
+                Error::<Test>::InvalidGeohash
+            );
+        });
+    }
+
+    #[test]
+    fn distance_tolerance_covers_cells_within_metres() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+
+            // Cells of length 5 are about 4.9km wide at the equator, but only about 3km at this
+            // latitude
+            let near = create_with_tolerance(ALICE, "gcpuv", Tolerance::Metres(2_000));
+            assert_ok!(submit_location(ALICE, near, "gcpuyxr1"));
+            assert_noop!(
+                submit_location(BOB, near, "gcpuzz29"),
+                Error::<Test>::InvalidGeohash
+            );
+
+            let far = create_with_tolerance(ALICE, "gcpuv", Tolerance::Metres(4_000));
+            assert_ok!(submit_location(BOB, far, "gcpuzz29"));
+            assert_eq!(
+                Challenges::<Test>::get(far).map(|info| info.tolerance),
+                Some(Tolerance::Metres(4_000))
+            );
+
+            assert_noop!(
+                AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(ALICE),
+                    Geohash("gcpuv").into(),
+                    Tolerance::Metres(MaxToleranceMetres::get() + 1),
+                    Default::default(),
+                    0,
+                    100,
+                    None,
+                    1,
+                    None
+                ),
+                Error::<Test>::InvalidTolerance
+            );
+        });
+    }
+
+    fn cells(
+        geohashes: &[&'static str],
+    ) -> BoundedVec<BoundedVec<u8, MaxGeohashLength>, MaxChallengeCells> {
+        geohashes
+            .iter()
+            .map(|geohash| Geohash(geohash).into())
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("cells")
+    }
 
-            assert_ok!(AttendanceModule::set_oracle_public_key(
+    #[test]
+    fn location_in_any_cell_of_challenge_is_accepted() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            ChallengeDepositBase::set(10);
+            DepositPerByte::set(1);
+            assert_ok!(Balances::force_set_balance(
                 RuntimeOrigin::root(),
-                Dummy::default().to_raw_vec().try_into().expect("")
+                ALICE,
+                1_000
             ));
 
+            // A venue covering "gcpuv" and the two cells east of it
+            let cover = cells(&["gcpuy", "gcpuz"]);
             assert_ok!(AttendanceModule::create_challenge(
                 RuntimeOrigin::signed(ALICE),
-                Geohash("bcd").into()
+                Geohash("gcpuv").into(),
+                Tolerance::Exact,
+                cover.clone(),
+                0,
+                100,
+                None,
+                1,
+                None
             ));
+            let challenge = NextChallengeId::<Test>::get() - 1;
+            assert_eq!(ChallengeCells::<Test>::get(challenge), Some(cover.clone()));
+            let info = Challenges::<Test>::get(challenge).expect("challenge");
+            assert_eq!(
+                info.deposit,
+                10 + (info.encoded_size() + cover.encoded_size()) as u64
+            );
 
-            let signature = Dummy::default();
+            assert_ok!(submit_location(ALICE, challenge, "gcpuvxr1"));
+            assert_ok!(submit_location(BOB, challenge, "gcpuzz29"));
+            assert_noop!(
+                submit_location(CHARLIE, challenge, "gcpuuxr1"),
+                Error::<Test>::InvalidGeohash
+            );
 
-            assert_ok!(AttendanceModule::submission_with_signature(
+            // The cells are cleared with the challenge
+            assert_ok!(AttendanceModule::close_challenge(
                 RuntimeOrigin::signed(ALICE),
-                Geohash("bcd").into(),
-                Geohash("bcdefg").into(),
-                signature
-                    .to_raw_vec()
-                    .try_into()
-                    .expect("signature to vector"),
+                challenge
+            ));
+            assert_ok!(AttendanceModule::reap_challenge(
+                RuntimeOrigin::signed(BOB),
+                challenge,
+                10
             ));
+            assert!(!ChallengeCells::<Test>::contains_key(challenge));
         });
     }
 
     #[test]
-    fn submit_proof_for_challenge() {
+    fn create_challenge_with_invalid_cells() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            for (cover, error) in [
+                (cells(&["gcpuy", "gcpua"]), Error::<Test>::InvalidGeohash),
+                (cells(&["gcpuy", "g"]), Error::<Test>::InvalidPrecision),
+            ] {
+                assert_noop!(
+                    AttendanceModule::create_challenge(
+                        RuntimeOrigin::signed(ALICE),
+                        Geohash("gcpuv").into(),
+                        Tolerance::Exact,
+                        cover,
+                        0,
+                        100,
+                        None,
+                        1,
+                        None
+                    ),
+                    error
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn signature_cannot_be_replayed_by_another_account() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let oracle = oracle();
+            set_oracle(&oracle);
+
+            let challenge = create(ALICE, "bcd");
+
+            let signature = attest(&oracle, ALICE, challenge, Geohash("bcdefg"), 10, 0);
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(BOB),
+                    challenge,
+                    Geohash("bcdefg").into(),
+                    10,
+                    0,
+                    attestations(vec![(public(&oracle()), signature)]),
+                ),
+                Error::<Test>::InvalidSignature
+            );
+        });
+    }
+
+    #[test]
+    fn signature_cannot_be_replayed_against_another_challenge() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let oracle = oracle();
+            set_oracle(&oracle);
+
+            let first = create(ALICE, "bcd");
+            let second = create(ALICE, "bcd");
+
+            let signature = attest(&oracle, ALICE, first, Geohash("bcdefg"), 10, 0);
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(ALICE),
+                    second,
+                    Geohash("bcdefg").into(),
+                    10,
+                    0,
+                    attestations(vec![(public(&oracle()), signature)]),
+                ),
+                Error::<Test>::InvalidSignature
+            );
+        });
+    }
+
+    #[test]
+    fn expired_attestation_is_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(11);
+            let oracle = oracle();
+            set_oracle(&oracle);
+
+            let challenge = create(ALICE, "bcd");
+
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(ALICE),
+                    challenge,
+                    Geohash("bcdefg").into(),
+                    10,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle, ALICE, challenge, Geohash("bcdefg"), 10, 0)
+                    )]),
+                ),
+                Error::<Test>::AttestationExpired
+            );
+        });
+    }
+
+    #[test]
+    fn attestation_nonce_cannot_be_reused() {
         new_test_ext().execute_with(|| {
             System::set_block_number(1);
+            set_oracle(&oracle());
+
+            let first = create(ALICE, "bcd");
+            let second = create(ALICE, "bc");
+
+            submit(ALICE, first, "bcdefg", 0);
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(ALICE),
+                    second,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), ALICE, second, Geohash("bcdefg"), 100, 0)
+                    )]),
+                ),
+                Error::<Test>::NonceAlreadyUsed
+            );
+        });
+    }
+
+    #[test]
+    fn create_challenge_with_invalid_window() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(5);
+            assert_noop!(
+                AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(ALICE),
+                    Geohash("bcd").into(),
+                    Tolerance::Exact,
+                    Default::default(),
+                    10,
+                    10,
+                    None,
+                    1,
+                    None
+                ),
+                Error::<Test>::InvalidWindow
+            );
+            assert_noop!(
+                AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(ALICE),
+                    Geohash("bcd").into(),
+                    Tolerance::Exact,
+                    Default::default(),
+                    1,
+                    5,
+                    None,
+                    1,
+                    None
+                ),
+                Error::<Test>::InvalidWindow
+            );
+        });
+    }
 
+    #[test]
+    fn submission_outside_window_is_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
             assert_ok!(AttendanceModule::create_challenge(
                 RuntimeOrigin::signed(ALICE),
-                Geohash("bcd").into()
+                Geohash("bcd").into(),
+                Tolerance::Exact,
+                Default::default(),
+                5,
+                10,
+                None,
+                1,
+                None
             ));
 
-            
-            let signature = Dummy::default();
+            let signature = attest(&oracle(), BOB, 0, Geohash("bcdefg"), 100, 0);
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(BOB),
+                    0,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![(public(&oracle()), signature.clone())]),
+                ),
+                Error::<Test>::ChallengeNotOpen
+            );
+
+            System::set_block_number(10);
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(BOB),
+                    0,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![(public(&oracle()), signature)]),
+                ),
+                Error::<Test>::ChallengeNotOpen
+            );
+        });
+    }
 
-            assert_ok!(AttendanceModule::submission_with_signature(
+    #[test]
+    fn submission_for_unknown_challenge_is_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(BOB),
+                    0,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), BOB, 0, Geohash("bcdefg"), 100, 0)
+                    )]),
+                ),
+                Error::<Test>::UnknownChallenge
+            );
+        });
+    }
+
+    #[test]
+    fn challenge_is_limited_to_max_attendees() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            assert_ok!(AttendanceModule::create_challenge(
                 RuntimeOrigin::signed(ALICE),
                 Geohash("bcd").into(),
-                Geohash("bcdefg").into(),
-                signature
-                    .to_raw_vec()
-                    .try_into()
-                    .expect("signature to vector"),
+                Tolerance::Exact,
+                Default::default(),
+                0,
+                10,
+                Some(1),
+                1,
+                None
+            ));
+
+            submit(ALICE, 0, "bcdefg", 0);
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(BOB),
+                    0,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), BOB, 0, Geohash("bcdefg"), 100, 0)
+                    )]),
+                ),
+                Error::<Test>::ChallengeFull
+            );
+        });
+    }
+
+    #[test]
+    fn only_organizer_can_close_or_cancel_challenge() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let first = create(ALICE, "bcd");
+            let second = create(ALICE, "bce");
+
+            assert_noop!(
+                AttendanceModule::close_challenge(RuntimeOrigin::signed(BOB), first),
+                Error::<Test>::NotOrganizer
+            );
+            assert_noop!(
+                AttendanceModule::cancel_challenge(RuntimeOrigin::signed(BOB), first),
+                Error::<Test>::NotOrganizer
+            );
+
+            assert_ok!(AttendanceModule::close_challenge(
+                RuntimeOrigin::signed(ALICE),
+                first
+            ));
+            assert_ok!(AttendanceModule::cancel_challenge(
+                RuntimeOrigin::signed(ALICE),
+                second
+            ));
+            assert_eq!(
+                Challenges::<Test>::get(first).expect("challenge").status,
+                ChallengeStatus::Closed
+            );
+            assert_eq!(
+                Challenges::<Test>::get(second).expect("challenge").status,
+                ChallengeStatus::Cancelled
+            );
+            assert_noop!(
+                AttendanceModule::close_challenge(RuntimeOrigin::signed(ALICE), second),
+                Error::<Test>::ChallengeNotOpen
+            );
+        });
+    }
+
+    #[test]
+    fn expired_challenges_are_closed_on_initialize() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let challenge = create(ALICE, "bcd");
+
+            AttendanceModule::on_initialize(100);
+            assert_eq!(
+                Challenges::<Test>::get(challenge)
+                    .expect("challenge")
+                    .status,
+                ChallengeStatus::Closed
+            );
+            System::assert_last_event(Event::ChallengeClosed { challenge }.into());
+        });
+    }
+
+    #[test]
+    fn full_expiry_block_spills_over_to_later_blocks() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let full: Vec<_> = (0..MaxChallengesPerBlock::get())
+                .map(|_| create(ALICE, "bcd"))
+                .collect();
+            let spilled = create(BOB, "bcd");
+            assert_eq!(ChallengeExpiries::<Test>::get(100).to_vec(), full);
+            assert_eq!(ChallengeExpiries::<Test>::get(101).to_vec(), vec![spilled]);
+
+            // Until every block it can spill over to is full
+            let slots = (EXPIRY_SPILLOVER_BLOCKS + 1) * MaxChallengesPerBlock::get();
+            for _ in full.len() as u32 + 1..slots {
+                create(BOB, "bcd");
+            }
+            assert_noop!(
+                AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(BOB),
+                    Geohash("bcd").into(),
+                    Tolerance::Exact,
+                    Default::default(),
+                    0,
+                    100,
+                    None,
+                    1,
+                    None
+                ),
+                Error::<Test>::TooManyExpiries
+            );
+
+            // Submissions still stop at the end of the window
+            System::set_block_number(100);
+            assert_noop!(
+                submit_location(CHARLIE, spilled, "bcdefg"),
+                Error::<Test>::ChallengeNotOpen
+            );
+            AttendanceModule::on_initialize(101);
+            assert_eq!(
+                Challenges::<Test>::get(spilled).map(|info| info.status),
+                Some(ChallengeStatus::Closed)
+            );
+        });
+    }
+
+    #[test]
+    fn submit_proof_for_challenge() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+
+            let challenge = create(ALICE, "bcd");
+
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(ALICE),
+                    challenge,
+                    Geohash("bcdefg").into(),
+                    10,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), ALICE, challenge, Geohash("bcdefg"), 10, 0)
+                    )]),
+                ),
+                Error::<Test>::NoOracle
+            );
+        });
+    }
+
+    #[test]
+    fn add_oracle() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let info = Oracles::<Test>::get(public(&oracle())).expect("oracle");
+            assert_eq!(info.operator, public(&oracle()));
+            assert_eq!(info.activated_at, 1);
+            assert_eq!(info.retires_at, None);
+
+            assert_noop!(
+                AttendanceModule::add_oracle(
+                    RuntimeOrigin::root(),
+                    public(&oracle()),
+                    Default::default()
+                ),
+                Error::<Test>::OracleAlreadyRegistered
+            );
+            assert_noop!(
+                AttendanceModule::add_oracle(
+                    RuntimeOrigin::signed(ALICE),
+                    public(&ed25519::Pair::from_seed(&[8u8; 32])),
+                    Default::default()
+                ),
+                DispatchError::BadOrigin
+            );
+            assert_noop!(
+                AttendanceModule::add_oracle(
+                    RuntimeOrigin::root(),
+                    vec![1u8; 3].try_into().expect("key"),
+                    Default::default()
+                ),
+                Error::<Test>::InvalidPublicKey
+            );
+        });
+    }
+
+    #[test]
+    fn removed_oracle_is_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let challenge = create(ALICE, "bcd");
+
+            assert_ok!(AttendanceModule::remove_oracle(
+                RuntimeOrigin::root(),
+                public(&oracle())
             ));
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(ALICE),
+                    challenge,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), ALICE, challenge, Geohash("bcdefg"), 100, 0)
+                    )]),
+                ),
+                Error::<Test>::NoOracle
+            );
+            assert_noop!(
+                AttendanceModule::remove_oracle(RuntimeOrigin::root(), public(&oracle())),
+                Error::<Test>::NoOracle
+            );
         });
     }
+
     #[test]
-    fn set_oracle_public_key() {
+    fn rotated_oracle_is_accepted_during_grace_period() {
         new_test_ext().execute_with(|| {
             System::set_block_number(1);
-            assert_ok!(AttendanceModule::set_oracle_public_key(
+            let new = ed25519::Pair::from_seed(&[8u8; 32]);
+            set_oracle(&oracle());
+            let challenge = create(ALICE, "bcd");
+
+            assert_ok!(AttendanceModule::rotate_oracle(
                 RuntimeOrigin::root(),
-                Dummy::default().to_raw_vec().try_into().expect("")
+                public(&oracle()),
+                public(&new)
             ));
+            System::assert_last_event(
+                Event::OracleRotated {
+                    old: public(&oracle()),
+                    new: public(&new),
+                    retires_at: 1 + OracleRotationPeriod::get(),
+                }
+                .into(),
+            );
+
+            // Both keys are accepted during the grace period
+            submit(ALICE, challenge, "bcdefg", 0);
+            assert_ok!(AttendanceModule::submission_with_signature(
+                RuntimeOrigin::signed(BOB),
+                challenge,
+                Geohash("bcdefg").into(),
+                100,
+                0,
+                attestations(vec![(
+                    public(&new),
+                    attest(&new, BOB, challenge, Geohash("bcdefg"), 100, 0)
+                )]),
+            ));
+
+            // Only the new key is accepted afterwards
+            System::set_block_number(1 + OracleRotationPeriod::get());
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(CHARLIE),
+                    challenge,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), CHARLIE, challenge, Geohash("bcdefg"), 100, 0)
+                    )]),
+                ),
+                Error::<Test>::NoOracle
+            );
+            assert_ok!(AttendanceModule::submission_with_signature(
+                RuntimeOrigin::signed(CHARLIE),
+                challenge,
+                Geohash("bcdefg").into(),
+                100,
+                0,
+                attestations(vec![(
+                    public(&new),
+                    attest(&new, CHARLIE, challenge, Geohash("bcdefg"), 100, 0)
+                )]),
+            ));
+        });
+    }
+
+    #[test]
+    fn rotated_oracle_counts_once_towards_threshold() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let new = ed25519::Pair::from_seed(&[8u8; 32]);
+            set_oracle(&oracle());
+            assert_ok!(AttendanceModule::rotate_oracle(
+                RuntimeOrigin::root(),
+                public(&oracle()),
+                public(&new)
+            ));
+            assert_eq!(
+                Oracles::<Test>::get(public(&new)).map(|info| info.operator),
+                Some(public(&oracle()))
+            );
+            assert_ok!(AttendanceModule::create_challenge(
+                RuntimeOrigin::signed(ALICE),
+                Geohash("bcd").into(),
+                Tolerance::Exact,
+                Default::default(),
+                0,
+                100,
+                None,
+                2,
+                None
+            ));
+
+            // The old and new keys belong to the same operator
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(BOB),
+                    0,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![
+                        (
+                            public(&oracle()),
+                            attest(&oracle(), BOB, 0, Geohash("bcdefg"), 100, 0)
+                        ),
+                        (
+                            public(&new),
+                            attest(&new, BOB, 0, Geohash("bcdefg"), 100, 0)
+                        ),
+                    ]),
+                ),
+                Error::<Test>::DuplicateOracle
+            );
+        });
+    }
+
+    #[test]
+    fn create_challenge_with_invalid_threshold() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            for threshold in [0, MaxAttestations::get() + 1] {
+                assert_noop!(
+                    AttendanceModule::create_challenge(
+                        RuntimeOrigin::signed(ALICE),
+                        Geohash("bcd").into(),
+                        Tolerance::Exact,
+                        Default::default(),
+                        1,
+                        10,
+                        None,
+                        threshold,
+                        None
+                    ),
+                    Error::<Test>::InvalidThreshold
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn submission_requires_threshold_of_oracles() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let second = ed25519::Pair::from_seed(&[8u8; 32]);
+            let rogue = ed25519::Pair::from_seed(&[9u8; 32]);
+            set_oracle(&oracle());
+            set_oracle(&second);
+            assert_ok!(AttendanceModule::create_challenge(
+                RuntimeOrigin::signed(ALICE),
+                Geohash("bcd").into(),
+                Tolerance::Exact,
+                Default::default(),
+                0,
+                100,
+                None,
+                2,
+                None
+            ));
+            let signed_by = |pair: &ed25519::Pair| {
+                (
+                    public(pair),
+                    attest(pair, BOB, 0, Geohash("bcdefg"), 100, 0),
+                )
+            };
+            let submit_with = |entries| {
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(BOB),
+                    0,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(entries),
+                )
+            };
+
+            // A single oracle can't satisfy the threshold on its own
+            assert_noop!(
+                submit_with(vec![signed_by(&oracle())]),
+                Error::<Test>::ThresholdNotMet
+            );
+            assert_noop!(
+                submit_with(vec![signed_by(&oracle()), signed_by(&oracle())]),
+                Error::<Test>::DuplicateOracle
+            );
+            assert_noop!(
+                submit_with(vec![signed_by(&oracle()), signed_by(&rogue)]),
+                Error::<Test>::NoOracle
+            );
+
+            assert_ok!(submit_with(vec![signed_by(&oracle()), signed_by(&second)]));
+            System::assert_last_event(
+                Event::SubmissionAccepted {
+                    who: BOB,
+                    challenge: 0,
+                    attestations: attestations(vec![signed_by(&oracle()), signed_by(&second)]),
+                }
+                .into(),
+            );
+            assert_eq!(Challenges::<Test>::get(0).expect("challenge").attendees, 1);
+        });
+    }
+
+    #[test]
+    fn set_proof_verifying_key() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let key: BoundedVec<u8, MaxVerifyingKeyLength> = vec![1u8; 64].try_into().expect("key");
+
+            assert_noop!(
+                AttendanceModule::set_proof_verifying_key(
+                    RuntimeOrigin::signed(ALICE),
+                    0,
+                    key.clone()
+                ),
+                DispatchError::BadOrigin
+            );
+            assert_ok!(AttendanceModule::set_proof_verifying_key(
+                RuntimeOrigin::root(),
+                0,
+                key.clone()
+            ));
+            System::assert_last_event(
+                Event::VerifyingKeySet {
+                    circuit: 0,
+                    key: key.clone(),
+                }
+                .into(),
+            );
+            assert_eq!(VerifyingKeys::<Test>::get(0), Some(key.clone()));
+            assert_eq!(ActiveCircuit::<Test>::get(), Some(0));
+            assert_noop!(
+                AttendanceModule::set_proof_verifying_key(RuntimeOrigin::root(), 0, key),
+                Error::<Test>::CircuitAlreadyRegistered
+            );
+        });
+    }
+
+    #[test]
+    fn challenge_keeps_circuit_after_upgrade() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let without_key = create(ALICE, "bcd");
+            assert_noop!(
+                AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(BOB),
+                    without_key,
+                    vec![0u8; 64].try_into().expect("proof")
+                ),
+                Error::<Test>::MissingVerifyingKey
+            );
+
+            assert_ok!(AttendanceModule::set_proof_verifying_key(
+                RuntimeOrigin::root(),
+                0,
+                vec![1u8; 64].try_into().expect("key")
+            ));
+            let first = create(ALICE, "bcd");
+            assert_ok!(AttendanceModule::set_proof_verifying_key(
+                RuntimeOrigin::root(),
+                1,
+                vec![2u8; 64].try_into().expect("key")
+            ));
+            let second = create(ALICE, "bcd");
+
+            let circuit = |challenge| {
+                Challenges::<Test>::get(challenge)
+                    .expect("challenge")
+                    .circuit
+            };
+            assert_eq!(circuit(without_key), None);
+            assert_eq!(circuit(first), Some(0));
+            assert_eq!(circuit(second), Some(1));
+        });
+    }
+
+    #[test]
+    fn genesis_registers_verifying_keys() {
+        let mut storage = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .unwrap();
+        crate::GenesisConfig::<Test> {
+            verifying_keys: vec![
+                (0, vec![1u8; 64].try_into().expect("key")),
+                (3, vec![2u8; 64].try_into().expect("key")),
+            ],
+            ..Default::default()
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+        sp_io::TestExternalities::from(storage).execute_with(|| {
+            assert!(VerifyingKeys::<Test>::contains_key(0));
+            assert!(VerifyingKeys::<Test>::contains_key(3));
+            assert_eq!(ActiveCircuit::<Test>::get(), Some(3));
+        });
+    }
+
+    #[test]
+    fn genesis_registers_oracles_and_challenges() {
+        let mut storage = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .unwrap();
+        crate::GenesisConfig::<Test> {
+            verifying_keys: vec![(0, vec![1u8; 64].try_into().expect("key"))],
+            oracles: vec![(
+                public(&oracle()),
+                b"dev".to_vec().try_into().expect("metadata"),
+            )],
+            challenges: vec![(
+                ALICE,
+                Geohash("bcd").into(),
+                Tolerance::Exact,
+                100,
+                Some(10),
+                1,
+            )],
+            ..Default::default()
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+        sp_io::TestExternalities::from(storage).execute_with(|| {
+            System::set_block_number(1);
+            assert_eq!(
+                AttendanceModule::oracle_keys(),
+                vec![public(&oracle()).into_inner()]
+            );
+            let info = Challenges::<Test>::get(0).expect("challenge");
+            assert_eq!(info.organizer, ALICE);
+            assert_eq!(info.opens_at, 0);
+            assert_eq!(info.circuit, Some(0));
+            assert_eq!(NextChallengeId::<Test>::get(), 1);
+
+            // The genesis oracle can attest submissions straight away
+            submit(BOB, 0, "bcdefg", 0);
+            assert!(Submissions::<Test>::contains_key(0, BOB));
+        });
+    }
+
+    #[test]
+    fn genesis_config_round_trips_through_json() {
+        let config = crate::GenesisConfig::<Test> {
+            challenges: vec![(
+                ALICE,
+                Geohash("bcd").into(),
+                Tolerance::Metres(250),
+                100,
+                None,
+                1,
+            )],
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).expect("serialize");
+        let config: crate::GenesisConfig<Test> = serde_json::from_str(&json).expect("deserialize");
+
+        let mut storage = frame_system::GenesisConfig::<Test>::default()
+            .build_storage()
+            .unwrap();
+        config.assimilate_storage(&mut storage).unwrap();
+
+        sp_io::TestExternalities::from(storage).execute_with(|| {
+            let info = Challenges::<Test>::get(0).expect("challenge");
+            assert_eq!(info.tolerance, Tolerance::Metres(250));
+        });
+    }
+
+    // Set up a circuit for `geohash` and prove `location` is within it for a submission to
+    // `challenge` from `who`, using `seed` to randomise the proof
+    fn prove(
+        geohash: &str,
+        location: &str,
+        challenge: ChallengeId,
+        who: u64,
+        seed: u64,
+    ) -> (Vec<u8>, Proof<Bn254>) {
+        let circuit = CompareCircuit::new_from_str(geohash, location);
+        let (proving_key, verifying_key) =
+            setup_groth16(&mut StdRng::seed_from_u64(0), circuit.clone()).expect("setup");
+        let binding = AttendanceModule::proof_binding(challenge, &who);
+        let proof = create_proof(
+            &proving_key,
+            circuit.bound_to(binding.as_ref()),
+            &mut StdRng::seed_from_u64(seed),
+        )
+        .expect("proof");
+
+        let mut key = Vec::new();
+        verifying_key
+            .serialize_uncompressed(&mut key)
+            .expect("serialize key");
+        (key, proof)
+    }
+
+    fn uncompressed(proof: &Proof<Bn254>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        proof
+            .serialize_uncompressed(&mut bytes)
+            .expect("serialize proof");
+        bytes
+    }
+
+    fn set_verifying_key(circuit: u32, key: Vec<u8>) {
+        assert_ok!(AttendanceModule::set_proof_verifying_key(
+            RuntimeOrigin::root(),
+            circuit,
+            key.try_into().expect("key fits")
+        ));
+    }
+
+    #[test]
+    fn submit_proof_from_geohash_prover() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let challenge = NextChallengeId::<Test>::get();
+            let (key, proof) = prove("bcd", "bcdefg", challenge, ALICE, 0);
+            let (_, other) = prove("bcd", "bcdefg", challenge, BOB, 1);
+            set_verifying_key(0, key);
+            create(ALICE, "bcd");
+
+            // Both serializations of a proof are accepted
+            let mut compressed = Vec::new();
+            other
+                .serialize_compressed(&mut compressed)
+                .expect("serialize proof");
+            assert_ok!(AttendanceModule::submission_with_proof(
+                RuntimeOrigin::signed(ALICE),
+                challenge,
+                uncompressed(&proof).try_into().expect("proof fits")
+            ));
+            assert_ok!(AttendanceModule::submission_with_proof(
+                RuntimeOrigin::signed(BOB),
+                challenge,
+                compressed.try_into().expect("proof fits")
+            ));
+            assert!(Submissions::<Test>::contains_key(challenge, ALICE));
+            assert!(Submissions::<Test>::contains_key(challenge, BOB));
+            assert_eq!(
+                Challenges::<Test>::get(challenge)
+                    .expect("challenge")
+                    .attendees,
+                2
+            );
+        });
+    }
+
+    #[test]
+    fn random_proof_bytes_are_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (key, proof) = prove("bcd", "bcdefg", 0, BOB, 0);
+            set_verifying_key(0, key);
+            let challenge = create(ALICE, "bcd");
+            let valid = uncompressed(&proof);
+            let rejected: [DispatchError; 2] = [
+                Error::<Test>::MalformedProof.into(),
+                Error::<Test>::InvalidProof.into(),
+            ];
+
+            let rng = &mut StdRng::seed_from_u64(1);
+            for _ in 0..256 {
+                // Alternate between random bytes and valid proofs with a corrupted byte
+                let bytes = if rng.gen() {
+                    let mut bytes = vec![0u8; rng.gen_range(0..=MaxProofLength::get() as usize)];
+                    rng.fill_bytes(&mut bytes);
+                    bytes
+                } else {
+                    let mut bytes = valid.clone();
+                    let index = rng.gen_range(0..bytes.len());
+                    bytes[index] ^= rng.gen_range(1..=u8::MAX);
+                    bytes
+                };
+
+                let result = AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(BOB),
+                    challenge,
+                    bytes.try_into().expect("proof fits"),
+                );
+                assert!(
+                    matches!(result, Err(e) if rejected.contains(&e)),
+                    "unexpected result {:?}",
+                    result
+                );
+            }
+            assert!(!Submissions::<Test>::contains_key(challenge, BOB));
+        });
+    }
+
+    #[test]
+    fn random_verifying_key_bytes_are_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (_, proof) = prove("bcd", "bcdefg", 0, BOB, 0);
+
+            let rng = &mut StdRng::seed_from_u64(2);
+            for circuit in 0..32 {
+                let mut key = vec![0u8; rng.gen_range(0..=MaxVerifyingKeyLength::get() as usize)];
+                rng.fill_bytes(&mut key);
+                set_verifying_key(circuit, key);
+                // Close each challenge in a different block to stay under `MaxChallengesPerBlock`
+                assert_ok!(AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(ALICE),
+                    Geohash("bcd").into(),
+                    Tolerance::Exact,
+                    Default::default(),
+                    0,
+                    100 + circuit as u64,
+                    None,
+                    1,
+                    None
+                ));
+                let challenge = NextChallengeId::<Test>::get() - 1;
+
+                assert_noop!(
+                    AttendanceModule::submission_with_proof(
+                        RuntimeOrigin::signed(BOB),
+                        challenge,
+                        uncompressed(&proof).try_into().expect("proof fits")
+                    ),
+                    Error::<Test>::MalformedVerifyingKey
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn proof_for_another_geohash_length_is_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (key, proof) = prove("bcd", "bcdefg", 0, BOB, 0);
+            set_verifying_key(0, key);
+            let challenge = create(ALICE, "bcde");
+
+            assert_noop!(
+                AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(BOB),
+                    challenge,
+                    uncompressed(&proof).try_into().expect("proof fits")
+                ),
+                Error::<Test>::PublicInputMismatch
+            );
+        });
+    }
+
+    #[test]
+    fn proof_cannot_be_reused() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (key, proof) = prove("bcd", "bcdefg", 0, ALICE, 0);
+            set_verifying_key(0, key);
+            let first = create(ALICE, "bcd");
+            let second = create(ALICE, "bcd");
+
+            assert_ok!(AttendanceModule::submission_with_proof(
+                RuntimeOrigin::signed(ALICE),
+                first,
+                uncompressed(&proof).try_into().expect("proof fits")
+            ));
+            let nullifier = match System::events().last().map(|record| &record.event) {
+                Some(RuntimeEvent::AttendanceModule(Event::ProofSubmissionAccepted {
+                    who: ALICE,
+                    challenge,
+                    nullifier,
+                })) if *challenge == first => *nullifier,
+                event => panic!("unexpected event {:?}", event),
+            };
+            assert!(ProofNullifiers::<Test>::contains_key(first, nullifier));
+
+            assert_noop!(
+                AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(ALICE),
+                    first,
+                    uncompressed(&proof).try_into().expect("proof fits")
+                ),
+                Error::<Test>::AlreadySubmitted
+            );
+
+            // The proof is bound to the account and challenge it was made for, whichever
+            // serialization is submitted
+            let mut compressed = Vec::new();
+            proof
+                .serialize_compressed(&mut compressed)
+                .expect("serialize proof");
+            assert_noop!(
+                AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(BOB),
+                    first,
+                    compressed.try_into().expect("proof fits")
+                ),
+                Error::<Test>::InvalidProof
+            );
+            assert_noop!(
+                AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(ALICE),
+                    second,
+                    uncompressed(&proof).try_into().expect("proof fits")
+                ),
+                Error::<Test>::InvalidProof
+            );
+        });
+    }
+
+    #[test]
+    fn rerandomised_proof_from_another_account_is_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (key, proof) = prove("bcd", "bcdefg", 0, ALICE, 0);
+            let verifying_key =
+                VerifyingKey::<Bn254>::deserialize_uncompressed(&key[..]).expect("key");
+            set_verifying_key(0, key);
+            let challenge = create(ALICE, "bcd");
+
+            // A re-randomised proof has a different nullifier but still verifies for ALICE
+            let rerandomised = Groth16::<Bn254>::rerandomize_proof(
+                &verifying_key,
+                &proof,
+                &mut StdRng::seed_from_u64(1),
+            );
+            assert_ne!(uncompressed(&rerandomised), uncompressed(&proof));
+            assert_noop!(
+                AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(BOB),
+                    challenge,
+                    uncompressed(&rerandomised).try_into().expect("proof fits")
+                ),
+                Error::<Test>::InvalidProof
+            );
+            assert_ok!(AttendanceModule::submission_with_proof(
+                RuntimeOrigin::signed(ALICE),
+                challenge,
+                uncompressed(&rerandomised).try_into().expect("proof fits")
+            ));
+            assert!(!Submissions::<Test>::contains_key(challenge, BOB));
+        });
+    }
+
+    #[test]
+    fn proof_for_closed_or_unknown_challenge_is_rejected() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let (key, proof) = prove("bcd", "bcdefg", 0, BOB, 0);
+            set_verifying_key(0, key);
+            let challenge = create(ALICE, "bcd");
+            assert_ok!(AttendanceModule::close_challenge(
+                RuntimeOrigin::signed(ALICE),
+                challenge
+            ));
+
+            assert_noop!(
+                AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(BOB),
+                    challenge,
+                    uncompressed(&proof).try_into().expect("proof fits")
+                ),
+                Error::<Test>::ChallengeNotOpen
+            );
+            assert_noop!(
+                AttendanceModule::submission_with_proof(
+                    RuntimeOrigin::signed(BOB),
+                    challenge + 1,
+                    uncompressed(&proof).try_into().expect("proof fits")
+                ),
+                Error::<Test>::UnknownChallenge
+            );
+        });
+    }
+
+    #[test]
+    fn badges_are_minted_for_each_attendee() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let first = create(ALICE, "bcd");
+            let second = create(BOB, "bce");
+            COLLECTIONS.with(|c| assert_eq!(*c.borrow(), vec![(first, ALICE), (second, BOB)]));
+
+            submit(ALICE, first, "bcdefg", 0);
+            submit(BOB, first, "bcdefg", 0);
+            submit(ALICE, second, "bcefg", 1);
+            MINTS.with(|m| {
+                assert_eq!(
+                    *m.borrow(),
+                    vec![(ALICE, first, 0), (BOB, first, 1), (ALICE, second, 0)]
+                )
+            });
+        });
+    }
+
+    #[test]
+    fn failed_mint_rolls_back_submission() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let challenge = create(ALICE, "bcd");
+
+            FAIL_MINT.with(|f| *f.borrow_mut() = true);
+            assert_noop!(
+                AttendanceModule::submission_with_signature(
+                    RuntimeOrigin::signed(BOB),
+                    challenge,
+                    Geohash("bcdefg").into(),
+                    100,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), BOB, challenge, Geohash("bcdefg"), 100, 0)
+                    )]),
+                ),
+                DispatchError::Other("mint failed")
+            );
+            assert!(!Submissions::<Test>::contains_key(challenge, BOB));
+        });
+    }
+
+    #[test]
+    fn attendees_are_paid_from_reward_pot() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let challenge = create(ALICE, "bcd");
+            let pot = AttendanceModule::pot_account(challenge);
+            assert_ok!(Balances::force_set_balance(
+                RuntimeOrigin::root(),
+                pot,
+                Reward::get() + 5
+            ));
+
+            submit(BOB, challenge, "bcdefg", 0);
+            assert_eq!(Balances::free_balance(BOB), Reward::get());
+
+            // The remainder of the pot is paid once it runs low
+            submit(CHARLIE, challenge, "bcdefg", 0);
+            assert_eq!(Balances::free_balance(CHARLIE), 5);
+            assert_eq!(Balances::free_balance(pot), 0);
+
+            // Nothing is paid from an empty pot
+            submit(ALICE, challenge, "bcdefg", 0);
+            assert_eq!(Balances::free_balance(ALICE), 0);
+        });
+    }
+
+    #[test]
+    fn reward_too_small_for_new_account_is_skipped() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            ExistentialDeposit::set(Reward::get() + 1);
+            set_oracle(&oracle());
+            let challenge = create(ALICE, "bcd");
+            let pot = AttendanceModule::pot_account(challenge);
+            assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), pot, 100));
+            assert_ok!(Balances::force_set_balance(
+                RuntimeOrigin::root(),
+                CHARLIE,
+                100
+            ));
+
+            // The submission is still accepted without paying the reward
+            submit(BOB, challenge, "bcdefg", 0);
+            assert!(Submissions::<Test>::contains_key(challenge, BOB));
+            assert_eq!(Balances::free_balance(BOB), 0);
+
+            // An existing account is paid as usual
+            submit(CHARLIE, challenge, "bcdefg", 0);
+            assert_eq!(Balances::free_balance(CHARLIE), 100 + Reward::get());
+        });
+    }
+
+    #[test]
+    fn reward_pot_is_swept_to_organizer_on_reap() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let challenge = create(ALICE, "bcd");
+            let pot = AttendanceModule::pot_account(challenge);
+            assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), pot, 25));
+            submit(BOB, challenge, "bcdefg", 0);
+
+            assert_ok!(AttendanceModule::close_challenge(
+                RuntimeOrigin::signed(ALICE),
+                challenge
+            ));
+            assert_ok!(AttendanceModule::reap_challenge(
+                RuntimeOrigin::signed(BOB),
+                challenge,
+                10
+            ));
+            assert_eq!(Balances::free_balance(pot), 0);
+            assert_eq!(Balances::free_balance(ALICE), 25 - Reward::get());
+        });
+    }
+
+    fn create_with_budget(
+        who: u64,
+        max_attendees: Option<u32>,
+        budget: ChallengeBudget<u64>,
+    ) -> ChallengeId {
+        assert_ok!(AttendanceModule::create_challenge(
+            RuntimeOrigin::signed(who),
+            Geohash("bcd").into(),
+            Tolerance::Exact,
+            Default::default(),
+            0,
+            100,
+            max_attendees,
+            1,
+            Some(budget)
+        ));
+        NextChallengeId::<Test>::get() - 1
+    }
+
+    #[test]
+    fn budget_is_split_equally() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            assert_ok!(Balances::force_set_balance(
+                RuntimeOrigin::root(),
+                ALICE,
+                1_000
+            ));
+            assert_noop!(
+                AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(ALICE),
+                    Geohash("bcd").into(),
+                    Tolerance::Exact,
+                    Default::default(),
+                    0,
+                    100,
+                    None,
+                    1,
+                    Some(ChallengeBudget {
+                        amount: 100,
+                        payout: Payout::Equal
+                    })
+                ),
+                Error::<Test>::UnboundedAttendees
+            );
+
+            let challenge = create_with_budget(
+                ALICE,
+                Some(3),
+                ChallengeBudget {
+                    amount: 100,
+                    payout: Payout::Equal,
+                },
+            );
+            System::assert_last_event(
+                Event::BudgetFunded {
+                    challenge,
+                    organizer: ALICE,
+                    amount: 100,
+                }
+                .into(),
+            );
+            assert_eq!(Balances::free_balance(ALICE), 900);
+            assert_eq!(
+                Balances::balance_on_hold(&HoldReason::ChallengeBudget.into(), &ALICE),
+                100
+            );
+
+            submit(BOB, challenge, "bcdefg", 0);
+            submit(CHARLIE, challenge, "bcdefg", 0);
+            assert_eq!(Balances::free_balance(BOB), 33);
+            assert_eq!(Balances::free_balance(CHARLIE), 33);
+            assert_eq!(
+                Escrows::<Test>::get(challenge).expect("escrow").remaining,
+                34
+            );
+        });
+    }
+
+    #[test]
+    fn budget_is_paid_first_come_first_served() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            assert_ok!(Balances::force_set_balance(
+                RuntimeOrigin::root(),
+                ALICE,
+                1_000
+            ));
+            let challenge = create_with_budget(
+                ALICE,
+                None,
+                ChallengeBudget {
+                    amount: 50,
+                    payout: Payout::FirstCome(30),
+                },
+            );
+
+            submit(BOB, challenge, "bcdefg", 0);
+            System::assert_last_event(
+                Event::RewardPaid {
+                    challenge,
+                    who: BOB,
+                    amount: 30,
+                }
+                .into(),
+            );
+            submit(CHARLIE, challenge, "bcdefg", 0);
+            assert_eq!(Balances::free_balance(BOB), 30);
+            assert_eq!(Balances::free_balance(CHARLIE), 20);
+            assert_eq!(
+                Escrows::<Test>::get(challenge).expect("escrow").remaining,
+                0
+            );
+        });
+    }
+
+    #[test]
+    fn budget_shares_must_reach_existential_deposit() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            ExistentialDeposit::set(40);
+            set_oracle(&oracle());
+            assert_ok!(Balances::force_set_balance(
+                RuntimeOrigin::root(),
+                ALICE,
+                1_000
+            ));
+            for (max_attendees, payout) in [(Some(3), Payout::Equal), (None, Payout::FirstCome(30))]
+            {
+                assert_noop!(
+                    AttendanceModule::create_challenge(
+                        RuntimeOrigin::signed(ALICE),
+                        Geohash("bcd").into(),
+                        Tolerance::Exact,
+                        Default::default(),
+                        0,
+                        100,
+                        max_attendees,
+                        1,
+                        Some(ChallengeBudget {
+                            amount: 100,
+                            payout
+                        })
+                    ),
+                    Error::<Test>::InvalidBudget
+                );
+            }
+
+            // A remainder too small to pay is left for the organizer
+            let challenge = create_with_budget(
+                ALICE,
+                None,
+                ChallengeBudget {
+                    amount: 60,
+                    payout: Payout::FirstCome(50),
+                },
+            );
+            submit(BOB, challenge, "bcdefg", 0);
+            submit(CHARLIE, challenge, "bcdefg", 0);
+            assert!(Submissions::<Test>::contains_key(challenge, CHARLIE));
+            assert_eq!(Balances::free_balance(BOB), 50);
+            assert_eq!(Balances::free_balance(CHARLIE), 0);
+            assert_eq!(
+                Escrows::<Test>::get(challenge).expect("escrow").remaining,
+                10
+            );
+        });
+    }
+
+    #[test]
+    fn remaining_budget_is_refunded_after_close() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            assert_ok!(Balances::force_set_balance(
+                RuntimeOrigin::root(),
+                ALICE,
+                1_000
+            ));
+            let challenge = create_with_budget(
+                ALICE,
+                None,
+                ChallengeBudget {
+                    amount: 50,
+                    payout: Payout::FirstCome(20),
+                },
+            );
+            submit(BOB, challenge, "bcdefg", 0);
+
+            assert_noop!(
+                AttendanceModule::refund_budget(RuntimeOrigin::signed(BOB), challenge),
+                Error::<Test>::ChallengeStillOpen
+            );
+            assert_ok!(AttendanceModule::close_challenge(
+                RuntimeOrigin::signed(ALICE),
+                challenge
+            ));
+            assert_ok!(AttendanceModule::refund_budget(
+                RuntimeOrigin::signed(BOB),
+                challenge
+            ));
+            System::assert_last_event(
+                Event::BudgetRefunded {
+                    challenge,
+                    organizer: ALICE,
+                    amount: 30,
+                }
+                .into(),
+            );
+            assert_eq!(Balances::free_balance(ALICE), 980);
+            assert_eq!(
+                Balances::balance_on_hold(&HoldReason::ChallengeBudget.into(), &ALICE),
+                0
+            );
+            assert_noop!(
+                AttendanceModule::refund_budget(RuntimeOrigin::signed(BOB), challenge),
+                Error::<Test>::NoBudget
+            );
+        });
+    }
+
+    #[test]
+    fn challenge_deposit_is_held_from_organizer() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            ChallengeDepositBase::set(10);
+            DepositPerByte::set(1);
+            assert_ok!(Balances::force_set_balance(
+                RuntimeOrigin::root(),
+                ALICE,
+                1_000
+            ));
+            assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), BOB, 5));
+
+            let challenge = create(ALICE, "bcd");
+            let info = Challenges::<Test>::get(challenge).expect("challenge");
+            assert_eq!(info.deposit, 10 + info.encoded_size() as u64);
+            assert_eq!(
+                info.deposit,
+                AttendanceModule::challenge_deposit(&info, &[])
+            );
+            assert_eq!(
+                Balances::balance_on_hold(&HoldReason::ChallengeDeposit.into(), &ALICE),
+                info.deposit
+            );
+            assert_eq!(Balances::free_balance(ALICE), 1_000 - info.deposit);
+
+            assert_noop!(
+                AttendanceModule::create_challenge(
+                    RuntimeOrigin::signed(BOB),
+                    Geohash("bcd").into(),
+                    Tolerance::Exact,
+                    Default::default(),
+                    0,
+                    100,
+                    None,
+                    1,
+                    None
+                ),
+                TokenError::FundsUnavailable
+            );
+        });
+    }
+
+    #[test]
+    fn finished_challenge_is_reaped_in_batches() {
+        let mut ext = new_test_ext();
+        let (challenge, deposit, nullifier) = ext.execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            ChallengeDepositBase::set(10);
+            DepositPerByte::set(1);
+            assert_ok!(Balances::force_set_balance(
+                RuntimeOrigin::root(),
+                ALICE,
+                1_000
+            ));
+            let (key, proof) = prove("bcd", "bcdefg", 0, 4, 0);
+            set_verifying_key(0, key);
+            let challenge = create_with_budget(
+                ALICE,
+                None,
+                ChallengeBudget {
+                    amount: 50,
+                    payout: Payout::FirstCome(10),
+                },
+            );
+            let deposit = Challenges::<Test>::get(challenge)
+                .expect("challenge")
+                .deposit;
+            for who in [BOB, CHARLIE, 5] {
+                submit(who, challenge, "bcdefg", 0);
+            }
+            assert_ok!(AttendanceModule::submission_with_proof(
+                RuntimeOrigin::signed(4),
+                challenge,
+                uncompressed(&proof).try_into().expect("proof fits")
+            ));
+            let nullifier = ProofNullifiers::<Test>::iter_key_prefix(challenge)
+                .next()
+                .expect("nullifier");
+            (challenge, deposit, nullifier)
+        });
+        // Limits count entries removed from the backend, so commit the submissions to it
+        ext.commit_all().expect("committed");
+
+        ext.execute_with(|| {
+            assert_noop!(
+                AttendanceModule::reap_challenge(RuntimeOrigin::signed(BOB), challenge, 2),
+                Error::<Test>::ChallengeStillOpen
+            );
+            assert_ok!(AttendanceModule::close_challenge(
+                RuntimeOrigin::signed(ALICE),
+                challenge
+            ));
+
+            assert_ok!(AttendanceModule::reap_challenge(
+                RuntimeOrigin::signed(BOB),
+                challenge,
+                2
+            ));
+            System::assert_last_event(
+                Event::SubmissionsReaped {
+                    challenge,
+                    removed: 2,
+                }
+                .into(),
+            );
+            assert!(Challenges::<Test>::contains_key(challenge));
+            assert!(ReapCursors::<Test>::contains_key(challenge));
+            assert_eq!(Submissions::<Test>::iter_prefix(challenge).count(), 2);
+            assert_eq!(UsedNonces::<Test>::iter_prefix(challenge).count(), 3);
+
+            // The rest of the submissions, then their nonces and nullifiers, are cleared
+            assert_ok!(AttendanceModule::reap_challenge(
+                RuntimeOrigin::signed(BOB),
+                challenge,
+                10
+            ));
+            System::assert_has_event(
+                Event::SubmissionsReaped {
+                    challenge,
+                    removed: 6,
+                }
+                .into(),
+            );
+            System::assert_has_event(
+                Event::BudgetRefunded {
+                    challenge,
+                    organizer: ALICE,
+                    amount: 10,
+                }
+                .into(),
+            );
+            System::assert_last_event(
+                Event::ChallengeReaped {
+                    challenge,
+                    organizer: ALICE,
+                    deposit,
+                }
+                .into(),
+            );
+            assert!(!Challenges::<Test>::contains_key(challenge));
+            assert!(!ReapCursors::<Test>::contains_key(challenge));
+            assert_eq!(Submissions::<Test>::iter_prefix(challenge).count(), 0);
+            assert_eq!(UsedNonces::<Test>::iter_prefix(challenge).count(), 0);
+            assert!(!ProofNullifiers::<Test>::contains_key(challenge, nullifier));
+            // Everything but the rewards paid to attendees is returned to ALICE
+            assert_eq!(Balances::free_balance(ALICE), 960);
+            assert_eq!(Balances::total_balance_on_hold(&ALICE), 0);
+
+            assert_noop!(
+                AttendanceModule::reap_challenge(RuntimeOrigin::signed(BOB), challenge, 2),
+                Error::<Test>::UnknownChallenge
+            );
+        });
+    }
+
+    #[test]
+    fn attendance_state_can_be_queried() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let first = create(ALICE, "bcd");
+            let second = create(ALICE, "bcf");
+            let other = create(ALICE, "uvw");
+
+            let found: Vec<_> = AttendanceModule::challenges_for_geohash(b"bc", 0)
+                .into_iter()
+                .map(|(challenge, _)| challenge)
+                .collect();
+            assert_eq!(found.len(), 2);
+            assert!(found.contains(&first) && found.contains(&second));
+            assert!(AttendanceModule::challenges_for_geohash(b"bc", 1).is_empty());
+            assert_eq!(
+                AttendanceModule::challenges_for_geohash(b"uvw", 0),
+                vec![(other, Challenges::<Test>::get(other).expect("challenge"))]
+            );
+            // Prefixes shorter than an area aren't searched
+            assert!(AttendanceModule::challenges_for_geohash(b"b", 0).is_empty());
+            assert!(AttendanceModule::challenges_for_geohash(b"", 0).is_empty());
+            let all: Vec<_> = AttendanceModule::challenges(0)
+                .into_iter()
+                .map(|(challenge, _)| challenge)
+                .collect();
+            assert_eq!(all, vec![first, second, other]);
+            assert!(AttendanceModule::challenges(1).is_empty());
+
+            submit(BOB, first, "bcdefg", 0);
+            submit(CHARLIE, first, "bcdefg", 0);
+            assert!(AttendanceModule::has_attended(first, &BOB));
+            assert!(!AttendanceModule::has_attended(second, &BOB));
+            let mut attendees = AttendanceModule::attendees(first, 0);
+            attendees.sort();
+            assert_eq!(attendees, vec![BOB, CHARLIE]);
+            assert!(AttendanceModule::attendees(first, 1).is_empty());
+            assert_eq!(
+                AttendanceModule::attended(&BOB, 0),
+                vec![(first, Challenges::<Test>::get(first).expect("challenge"))]
+            );
+            assert!(AttendanceModule::attended(&ALICE, 0).is_empty());
+            assert!(AttendanceModule::attended(&BOB, 1).is_empty());
+
+            assert_eq!(
+                AttendanceModule::oracle_keys(),
+                vec![public(&oracle()).into_inner()]
+            );
+            let new = ed25519::Pair::from_seed(&[8u8; 32]);
+            assert_ok!(AttendanceModule::rotate_oracle(
+                RuntimeOrigin::root(),
+                public(&oracle()),
+                public(&new)
+            ));
+            System::set_block_number(1 + OracleRotationPeriod::get());
+            assert_eq!(
+                AttendanceModule::oracle_keys(),
+                vec![public(&new).into_inner()]
+            );
+
+            assert_eq!(AttendanceModule::verifying_key(), None);
+            set_verifying_key(3, vec![1, 2, 3]);
+            assert_eq!(AttendanceModule::verifying_key(), Some((3, vec![1, 2, 3])));
+
+            // Reaping a challenge removes it from the indexes
+            assert_ok!(AttendanceModule::close_challenge(
+                RuntimeOrigin::signed(ALICE),
+                first
+            ));
+            assert_ok!(AttendanceModule::reap_challenge(
+                RuntimeOrigin::signed(BOB),
+                first,
+                10
+            ));
+            assert!(AttendanceModule::attended(&BOB, 0).is_empty());
+            assert!(!AttendedChallenges::<Test>::contains_key(CHARLIE, first));
+            assert_eq!(
+                AttendanceModule::challenges_for_geohash(b"bcd", 0),
+                Vec::new()
+            );
+            assert_eq!(AttendanceModule::challenges(0).len(), 2);
+        });
+    }
+
+    #[test]
+    fn submissions_can_be_validated_without_submitting() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let challenge = create(ALICE, "bcd");
+            let validate = |who: u64, call: &crate::Call<Test>| {
+                AttendanceModule::validate_submission(&who, call).map_err(DispatchError::from)
+            };
+            let signed = |who: u64, nonce: u64| crate::Call::<Test>::submission_with_signature {
+                challenge,
+                location: Geohash("bcdefg").into(),
+                expires_at: 100,
+                nonce,
+                attestations: attestations(vec![(
+                    public(&oracle()),
+                    attest(&oracle(), who, challenge, Geohash("bcdefg"), 100, nonce),
+                )]),
+            };
+
+            let events = System::events().len();
+            assert_ok!(validate(BOB, &signed(BOB, 0)));
+            assert!(!Submissions::<Test>::contains_key(challenge, BOB));
+            assert_eq!(System::events().len(), events);
+            assert!(matches!(
+                AttendanceModule::validate_submission(&CHARLIE, &signed(BOB, 0)),
+                Err(SubmissionError::Attendance(Error::<Test>::InvalidSignature))
+            ));
+
+            // Failures after the checks pass are reported too
+            FAIL_MINT.with(|f| *f.borrow_mut() = true);
+            assert_noop!(
+                validate(BOB, &signed(BOB, 0)),
+                DispatchError::Other("mint failed")
+            );
+            FAIL_MINT.with(|f| *f.borrow_mut() = false);
+            assert_noop!(
+                validate(CHARLIE, &signed(BOB, 0)),
+                Error::<Test>::InvalidSignature
+            );
+            submit(BOB, challenge, "bcdefg", 0);
+            assert_noop!(
+                validate(BOB, &signed(BOB, 1)),
+                Error::<Test>::AlreadySubmitted
+            );
+
+            let (key, proof) = prove("bcd", "bcdefg", challenge, CHARLIE, 0);
+            let proven = crate::Call::<Test>::submission_with_proof {
+                challenge,
+                proof: uncompressed(&proof).try_into().expect("proof fits"),
+            };
+            assert_noop!(
+                validate(CHARLIE, &proven),
+                Error::<Test>::MissingVerifyingKey
+            );
+            set_verifying_key(0, key);
+            let challenge = create(ALICE, "bcd");
+            let (_, proof) = prove("bcd", "bcdefg", challenge, CHARLIE, 0);
+            let proven = crate::Call::<Test>::submission_with_proof {
+                challenge,
+                proof: uncompressed(&proof).try_into().expect("proof fits"),
+            };
+            assert_ok!(validate(CHARLIE, &proven));
+
+            assert_noop!(
+                validate(ALICE, &crate::Call::<Test>::close_challenge { challenge }),
+                Error::<Test>::NotASubmission
+            );
+        });
+    }
+
+    #[test]
+    fn unsigned_submission_is_authorised_by_attestations() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            let challenge = create(ALICE, "bcd");
+            // An attendee without any balance to pay fees with
+            let attendee = 42;
+            let unsigned = |attendee: u64, signer: u64, nonce: u64| {
+                crate::Call::<Test>::unsigned_submission_with_signature {
+                    attendee,
+                    challenge,
+                    location: Geohash("bcdefg").into(),
+                    expires_at: 50,
+                    nonce,
+                    attestations: attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), signer, challenge, Geohash("bcdefg"), 50, nonce),
+                    )]),
+                }
+            };
+            let validate = |call: &crate::Call<Test>| {
+                AttendanceModule::validate_unsigned(TransactionSource::External, call)
+            };
+
+            // Valid until the attestation expires, and only once per attendee
+            assert_eq!(
+                validate(&unsigned(attendee, attendee, 0)),
+                Ok(ValidTransaction {
+                    // The challenge closes in 99 blocks
+                    priority: UnsignedPriority::get() - 99,
+                    requires: vec![],
+                    provides: vec![("Attendance", challenge, attendee).encode()],
+                    longevity: 49,
+                    propagate: true,
+                })
+            );
+            assert_eq!(
+                validate(&unsigned(attendee, BOB, 0)),
+                Err(InvalidTransaction::BadProof.into())
+            );
+            assert_eq!(
+                validate(&crate::Call::<Test>::close_challenge { challenge }),
+                Err(InvalidTransaction::Call.into())
+            );
+
+            assert_noop!(
+                AttendanceModule::unsigned_submission_with_signature(
+                    RuntimeOrigin::signed(attendee),
+                    attendee,
+                    challenge,
+                    Geohash("bcdefg").into(),
+                    50,
+                    0,
+                    attestations(vec![(
+                        public(&oracle()),
+                        attest(&oracle(), attendee, challenge, Geohash("bcdefg"), 50, 0),
+                    )]),
+                ),
+                DispatchError::BadOrigin
+            );
+            assert_ok!(AttendanceModule::unsigned_submission_with_signature(
+                RuntimeOrigin::none(),
+                attendee,
+                challenge,
+                Geohash("bcdefg").into(),
+                50,
+                0,
+                attestations(vec![(
+                    public(&oracle()),
+                    attest(&oracle(), attendee, challenge, Geohash("bcdefg"), 50, 0),
+                )]),
+            ));
+            assert!(Submissions::<Test>::contains_key(challenge, attendee));
+            assert_eq!(
+                validate(&unsigned(attendee, attendee, 1)),
+                Err(InvalidTransaction::Stale.into())
+            );
+        });
+    }
+
+    #[test]
+    fn unsigned_submission_outside_window_is_future_or_stale() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            set_oracle(&oracle());
+            assert_ok!(AttendanceModule::create_challenge(
+                RuntimeOrigin::signed(ALICE),
+                Geohash("bcd").into(),
+                Tolerance::Exact,
+                Default::default(),
+                10,
+                100,
+                None,
+                1,
+                None
+            ));
+            let challenge = NextChallengeId::<Test>::get() - 1;
+            let validate = |nonce: u64| {
+                AttendanceModule::validate_unsigned(
+                    TransactionSource::External,
+                    &crate::Call::<Test>::unsigned_submission_with_signature {
+                        attendee: BOB,
+                        challenge,
+                        location: Geohash("bcdefg").into(),
+                        expires_at: 200,
+                        nonce,
+                        attestations: attestations(vec![(
+                            public(&oracle()),
+                            attest(&oracle(), BOB, challenge, Geohash("bcdefg"), 200, nonce),
+                        )]),
+                    },
+                )
+                .map(|valid| valid.priority)
+            };
+
+            assert_eq!(validate(0), Err(InvalidTransaction::Future.into()));
+            System::set_block_number(10);
+            assert_eq!(validate(0), Ok(UnsignedPriority::get() - 90));
+            System::set_block_number(99);
+            assert_eq!(validate(0), Ok(UnsignedPriority::get() - 1));
+            System::set_block_number(100);
+            assert_eq!(validate(0), Err(InvalidTransaction::Stale.into()));
         });
     }
 }
@@ -1,29 +1,20 @@
-
-//! Autogenerated weights for pallet_template
+//! Placeholder weights for pallet_attendance.
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-04-06, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `Alexs-MacBook-Pro-2.local`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
-
-// Executed Command:
-// ../../target/release/node-template
-// benchmark
-// pallet
-// --chain
-// dev
-// --pallet
-// pallet_attendance
-// --extrinsic
-// *
-// --steps=50
-// --repeat=20
-// --wasm-execution=compiled
-// --output
-// pallets/attendance/src/weights.rs
-// --template
-// ../../.maintain/frame-weight-template.hbs
+//! These weights are hand-written estimates, not benchmark results. The storage accessed by each
+//! call is listed above its weight, but the times are guesses. Replace this file with the output
+//! of `benchmark pallet` for `pallet_attendance`, run on reference hardware, before the pallet
+//! is used in production:
+//!
+//! ```sh
+//! cargo build --release --features runtime-benchmarks
+//! ./target/release/solochain-template-node benchmark pallet --chain dev \
+//!     --pallet pallet_attendance --extrinsic '*' --steps 50 --repeat 20 \
+//!     --output pallets/attendance/src/weights.rs \
+//!     --template frame-weight-template.hbs
+//! ```
+//!
+//! The template is `substrate/.maintain/frame-weight-template.hbs` from the Polkadot SDK, which
+//! generates `SubstrateWeight<T>` and the `()` implementation.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -34,57 +25,338 @@ use core::marker::PhantomData;
 
 /// Weight functions needed for pallet_attendance.
 pub trait WeightInfo {
-	fn do_something() -> Weight;
-	fn cause_error() -> Weight;
+	fn create_challenge(g: u32, c: u32) -> Weight;
+	fn submission_with_signature(a: u32, g: u32) -> Weight;
+	fn add_oracle(m: u32) -> Weight;
+	fn submission_with_proof(g: u32, c: u32) -> Weight;
+	fn close_challenge() -> Weight;
+	fn cancel_challenge() -> Weight;
+	fn remove_oracle() -> Weight;
+	fn rotate_oracle() -> Weight;
+	fn set_proof_verifying_key(k: u32) -> Weight;
+	fn refund_budget() -> Weight;
+	fn reap_challenge(n: u32) -> Weight;
+	fn unsigned_submission_with_signature(a: u32, g: u32) -> Weight;
+	fn on_initialize(n: u32) -> Weight;
 }
 
 /// Weights for pallet_attendance using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	/// Storage: AttendanceModule Something (r:0 w:1)
-	/// Proof: AttendanceModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	/// Storage: `AttendanceModule::NextChallengeId` (r:1 w:1)
+	/// Storage: `AttendanceModule::ChallengeExpiries` (r:17 w:1)
+	/// Storage: `AttendanceModule::ActiveCircuit` (r:1 w:0)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `AttendanceModule::Escrows` (r:0 w:1)
+	/// Storage: `AttendanceModule::Challenges` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengesByArea` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengeCells` (r:0 w:1)
+	/// The range of component `g` is `[4, 8]`.
+	/// The range of component `c` is `[0, 64]`.
+	fn create_challenge(g: u32, c: u32) -> Weight {
+		Weight::from_parts(66_000_000, 3_593)
+			.saturating_add(Weight::from_parts(21_000, 0).saturating_mul(g.into()))
+			.saturating_add(Weight::from_parts(95_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(21_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	/// Storage: `AttendanceModule::Submissions` (r:1 w:1)
+	/// Storage: `AttendanceModule::AttendedChallenges` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengeCells` (r:1 w:0)
+	/// Storage: `AttendanceModule::UsedNonces` (r:1 w:1)
+	/// Storage: `System::BlockHash` (r:1 w:0)
+	/// Storage: `AttendanceModule::Oracles` (r:8 w:0)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// The range of component `a` is `[1, 8]`.
+	/// The range of component `g` is `[4, 12]`.
+	fn submission_with_signature(a: u32, g: u32) -> Weight {
+		Weight::from_parts(82_000_000, 4_923)
+			.saturating_add(Weight::from_parts(54_000_000, 2_531).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(9_000, 0).saturating_mul(g.into()))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: `AttendanceModule::Oracles` (r:1 w:1)
+	/// The range of component `m` is `[0, 64]`.
+	fn add_oracle(m: u32) -> Weight {
+		Weight::from_parts(14_000_000, 3_553)
+			.saturating_add(Weight::from_parts(1_200, 0).saturating_mul(m.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	/// Storage: `AttendanceModule::Submissions` (r:1 w:1)
+	/// Storage: `AttendanceModule::AttendedChallenges` (r:0 w:1)
+	/// Storage: `AttendanceModule::VerifyingKeys` (r:1 w:0)
+	/// Storage: `AttendanceModule::ProofNullifiers` (r:1 w:1)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// The range of component `g` is `[4, 8]`.
+	/// The range of component `c` is `[0, 1]`.
+	fn submission_with_proof(g: u32, c: u32) -> Weight {
+		Weight::from_parts(4_350_000_000, 5_098)
+			.saturating_add(Weight::from_parts(185_000_000, 0).saturating_mul(g.into()))
+			.saturating_add(Weight::from_parts(410_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	fn close_challenge() -> Weight {
+		Weight::from_parts(19_000_000, 3_566)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
-	/// Storage: AttendanceModule Something (r:1 w:1)
-	/// Proof: AttendanceModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	fn cancel_challenge() -> Weight {
+		Weight::from_parts(19_000_000, 3_566)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `AttendanceModule::Oracles` (r:1 w:1)
+	fn remove_oracle() -> Weight {
+		Weight::from_parts(16_000_000, 3_553)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `AttendanceModule::Oracles` (r:2 w:2)
+	fn rotate_oracle() -> Weight {
+		Weight::from_parts(27_000_000, 6_106)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AttendanceModule::VerifyingKeys` (r:1 w:1)
+	/// Storage: `AttendanceModule::ActiveCircuit` (r:0 w:1)
+	/// The range of component `k` is `[1, 2048]`.
+	fn set_proof_verifying_key(k: u32) -> Weight {
+		Weight::from_parts(15_000_000, 3_541)
+			.saturating_add(Weight::from_parts(1_400, 0).saturating_mul(k.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:0)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn refund_budget() -> Weight {
+		Weight::from_parts(52_000_000, 3_593)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	/// Storage: `AttendanceModule::ReapCursors` (r:1 w:1)
+	/// Storage: `AttendanceModule::Submissions` (r:1001 w:1000)
+	/// Storage: `AttendanceModule::AttendedChallenges` (r:0 w:1000)
+	/// Storage: `AttendanceModule::UsedNonces` (r:1 w:0)
+	/// Storage: `AttendanceModule::ProofNullifiers` (r:1 w:0)
+	/// Storage: `AttendanceModule::ChallengesByArea` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengeCells` (r:0 w:1)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// The range of component `n` is `[1, 1000]`.
+	fn reap_challenge(n: u32) -> Weight {
+		Weight::from_parts(61_000_000, 3_593)
+			.saturating_add(Weight::from_parts(4_900_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(7_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	/// Storage: `AttendanceModule::Submissions` (r:1 w:1)
+	/// Storage: `AttendanceModule::AttendedChallenges` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengeCells` (r:1 w:0)
+	/// Storage: `AttendanceModule::UsedNonces` (r:1 w:1)
+	/// Storage: `System::BlockHash` (r:1 w:0)
+	/// Storage: `AttendanceModule::Oracles` (r:8 w:0)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// The range of component `a` is `[1, 8]`.
+	/// The range of component `g` is `[4, 12]`.
+	fn unsigned_submission_with_signature(a: u32, g: u32) -> Weight {
+		Weight::from_parts(80_000_000, 4_923)
+			.saturating_add(Weight::from_parts(54_000_000, 2_531).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(9_000, 0).saturating_mul(g.into()))
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+	/// Storage: `AttendanceModule::ChallengeExpiries` (r:1 w:1)
+	/// Storage: `AttendanceModule::Challenges` (r:64 w:64)
+	/// The range of component `n` is `[0, 64]`.
+	fn on_initialize(n: u32) -> Weight {
+		Weight::from_parts(4_000_000, 1_489)
+			.saturating_add(Weight::from_parts(12_000_000, 2_566).saturating_mul(n.into()))
 			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	/// Storage: AttendanceModule Something (r:0 w:1)
-	/// Proof: AttendanceModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	/// Storage: `AttendanceModule::NextChallengeId` (r:1 w:1)
+	/// Storage: `AttendanceModule::ChallengeExpiries` (r:17 w:1)
+	/// Storage: `AttendanceModule::ActiveCircuit` (r:1 w:0)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// Storage: `AttendanceModule::Escrows` (r:0 w:1)
+	/// Storage: `AttendanceModule::Challenges` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengesByArea` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengeCells` (r:0 w:1)
+	/// The range of component `g` is `[4, 8]`.
+	/// The range of component `c` is `[0, 64]`.
+	fn create_challenge(g: u32, c: u32) -> Weight {
+		Weight::from_parts(66_000_000, 3_593)
+			.saturating_add(Weight::from_parts(21_000, 0).saturating_mul(g.into()))
+			.saturating_add(Weight::from_parts(95_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(21_u64))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	/// Storage: `AttendanceModule::Submissions` (r:1 w:1)
+	/// Storage: `AttendanceModule::AttendedChallenges` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengeCells` (r:1 w:0)
+	/// Storage: `AttendanceModule::UsedNonces` (r:1 w:1)
+	/// Storage: `System::BlockHash` (r:1 w:0)
+	/// Storage: `AttendanceModule::Oracles` (r:8 w:0)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// The range of component `a` is `[1, 8]`.
+	/// The range of component `g` is `[4, 12]`.
+	fn submission_with_signature(a: u32, g: u32) -> Weight {
+		Weight::from_parts(82_000_000, 4_923)
+			.saturating_add(Weight::from_parts(54_000_000, 2_531).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(9_000, 0).saturating_mul(g.into()))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: `AttendanceModule::Oracles` (r:1 w:1)
+	/// The range of component `m` is `[0, 64]`.
+	fn add_oracle(m: u32) -> Weight {
+		Weight::from_parts(14_000_000, 3_553)
+			.saturating_add(Weight::from_parts(1_200, 0).saturating_mul(m.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
-	/// Storage: AttendanceModule Something (r:1 w:1)
-	/// Proof: AttendanceModule Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	/// Storage: `AttendanceModule::Submissions` (r:1 w:1)
+	/// Storage: `AttendanceModule::AttendedChallenges` (r:0 w:1)
+	/// Storage: `AttendanceModule::VerifyingKeys` (r:1 w:0)
+	/// Storage: `AttendanceModule::ProofNullifiers` (r:1 w:1)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// The range of component `g` is `[4, 8]`.
+	/// The range of component `c` is `[0, 1]`.
+	fn submission_with_proof(g: u32, c: u32) -> Weight {
+		Weight::from_parts(4_350_000_000, 5_098)
+			.saturating_add(Weight::from_parts(185_000_000, 0).saturating_mul(g.into()))
+			.saturating_add(Weight::from_parts(410_000_000, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	fn close_challenge() -> Weight {
+		Weight::from_parts(19_000_000, 3_566)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	fn cancel_challenge() -> Weight {
+		Weight::from_parts(19_000_000, 3_566)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `AttendanceModule::Oracles` (r:1 w:1)
+	fn remove_oracle() -> Weight {
+		Weight::from_parts(16_000_000, 3_553)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `AttendanceModule::Oracles` (r:2 w:2)
+	fn rotate_oracle() -> Weight {
+		Weight::from_parts(27_000_000, 6_106)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AttendanceModule::VerifyingKeys` (r:1 w:1)
+	/// Storage: `AttendanceModule::ActiveCircuit` (r:0 w:1)
+	/// The range of component `k` is `[1, 2048]`.
+	fn set_proof_verifying_key(k: u32) -> Weight {
+		Weight::from_parts(15_000_000, 3_541)
+			.saturating_add(Weight::from_parts(1_400, 0).saturating_mul(k.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:0)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	fn refund_budget() -> Weight {
+		Weight::from_parts(52_000_000, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	/// Storage: `AttendanceModule::ReapCursors` (r:1 w:1)
+	/// Storage: `AttendanceModule::Submissions` (r:1001 w:1000)
+	/// Storage: `AttendanceModule::AttendedChallenges` (r:0 w:1000)
+	/// Storage: `AttendanceModule::UsedNonces` (r:1 w:0)
+	/// Storage: `AttendanceModule::ProofNullifiers` (r:1 w:0)
+	/// Storage: `AttendanceModule::ChallengesByArea` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengeCells` (r:0 w:1)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:1 w:1)
+	/// The range of component `n` is `[1, 1000]`.
+	fn reap_challenge(n: u32) -> Weight {
+		Weight::from_parts(61_000_000, 3_593)
+			.saturating_add(Weight::from_parts(4_900_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(7_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
+	}
+	/// Storage: `AttendanceModule::Challenges` (r:1 w:1)
+	/// Storage: `AttendanceModule::Submissions` (r:1 w:1)
+	/// Storage: `AttendanceModule::AttendedChallenges` (r:0 w:1)
+	/// Storage: `AttendanceModule::ChallengeCells` (r:1 w:0)
+	/// Storage: `AttendanceModule::UsedNonces` (r:1 w:1)
+	/// Storage: `System::BlockHash` (r:1 w:0)
+	/// Storage: `AttendanceModule::Oracles` (r:8 w:0)
+	/// Storage: `AttendanceModule::Escrows` (r:1 w:1)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// The range of component `a` is `[1, 8]`.
+	/// The range of component `g` is `[4, 12]`.
+	fn unsigned_submission_with_signature(a: u32, g: u32) -> Weight {
+		Weight::from_parts(80_000_000, 4_923)
+			.saturating_add(Weight::from_parts(54_000_000, 2_531).saturating_mul(a.into()))
+			.saturating_add(Weight::from_parts(9_000, 0).saturating_mul(g.into()))
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(a.into())))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+	/// Storage: `AttendanceModule::ChallengeExpiries` (r:1 w:1)
+	/// Storage: `AttendanceModule::Challenges` (r:64 w:64)
+	/// The range of component `n` is `[0, 64]`.
+	fn on_initialize(n: u32) -> Weight {
+		Weight::from_parts(4_000_000, 1_489)
+			.saturating_add(Weight::from_parts(12_000_000, 2_566).saturating_mul(n.into()))
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
 	}
 }
@@ -29,6 +29,7 @@ use frame_support::{
 	genesis_builder_helper::{build_state, get_preset},
 	weights::Weight,
 };
+use pallet_attendance::{ChallengeId, ChallengeInfoOf, CircuitId};
 use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -42,8 +43,8 @@ use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
-	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+	AccountId, AttendanceModule, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce,
+	Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
 };
 
 impl_runtime_apis! {
@@ -170,6 +171,60 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_attendance_runtime_api::AttendanceApi<
+		Block,
+		AccountId,
+		ChallengeInfoOf<Runtime>,
+		pallet_attendance::Call<Runtime>,
+		pallet_attendance::SubmissionError<Runtime>,
+	> for Runtime {
+		fn challenge(id: ChallengeId) -> Option<ChallengeInfoOf<Runtime>> {
+			pallet_attendance::Challenges::<Runtime>::get(id)
+		}
+
+		fn challenges(page: u32) -> Vec<(ChallengeId, ChallengeInfoOf<Runtime>)> {
+			AttendanceModule::challenges(page)
+		}
+
+		fn next_challenge_id() -> ChallengeId {
+			pallet_attendance::NextChallengeId::<Runtime>::get()
+		}
+
+		fn challenges_for_geohash(
+			prefix: Vec<u8>,
+			page: u32,
+		) -> Vec<(ChallengeId, ChallengeInfoOf<Runtime>)> {
+			AttendanceModule::challenges_for_geohash(&prefix, page)
+		}
+
+		fn has_attended(challenge: ChallengeId, account: AccountId) -> bool {
+			AttendanceModule::has_attended(challenge, &account)
+		}
+
+		fn attendees(challenge: ChallengeId, page: u32) -> Vec<AccountId> {
+			AttendanceModule::attendees(challenge, page)
+		}
+
+		fn attended(account: AccountId, page: u32) -> Vec<(ChallengeId, ChallengeInfoOf<Runtime>)> {
+			AttendanceModule::attended(&account, page)
+		}
+
+		fn oracle_keys() -> Vec<Vec<u8>> {
+			AttendanceModule::oracle_keys()
+		}
+
+		fn verifying_key() -> Option<(CircuitId, Vec<u8>)> {
+			AttendanceModule::verifying_key()
+		}
+
+		fn validate_submission(
+			account: AccountId,
+			call: pallet_attendance::Call<Runtime>,
+		) -> Result<(), pallet_attendance::SubmissionError<Runtime>> {
+			AttendanceModule::validate_submission(&account, &call)
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance> for Runtime {
 		fn query_info(
 			uxt: <Block as BlockT>::Extrinsic,
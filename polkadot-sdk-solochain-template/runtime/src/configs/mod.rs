@@ -23,29 +23,42 @@
 //
 // For more information, please refer to <http://unlicense.org>
 
-use core::marker::PhantomData;
+use alloc::format;
 
 // Substrate and Polkadot dependencies
 use frame_support::{
 	derive_impl, parameter_types,
-	traits::{ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, VariantCountOf},
+	traits::{
+		tokens::nonfungibles_v2::{Create, Mutate},
+		AsEnsureOriginWithArg, ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, VariantCountOf,
+	},
 	weights::{
 		constants::{RocksDbWeight, WEIGHT_REF_TIME_PER_SECOND},
 		IdentityFee, Weight,
 	},
+	PalletId, Twox64Concat,
+};
+use frame_system::{
+	limits::{BlockLength, BlockWeights},
+	EnsureNever, EnsureRoot,
 };
-use frame_system::limits::{BlockLength, BlockWeights};
-use pallet_attendance::Mintable;
+use pallet_attendance::{rewards::FungibleReward, ChallengeId, Mintable};
+use pallet_nfts::{PalletFeatures, WeightInfo as _};
 use pallet_transaction_payment::{ConstFeeMultiplier, FungibleAdapter, Multiplier};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::{traits::One, Perbill};
+use sp_runtime::{
+	traits::{AccountIdConversion, One, Verify},
+	transaction_validity::TransactionPriority,
+	DispatchResult, Perbill,
+};
 use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Balances, Block, BlockNumber, Hash, Nonce, PalletInfo, Runtime,
+	AccountId, Aura, Balance, Balances, Block, BlockNumber, Hash, Nfts, Nonce, PalletInfo, Runtime,
 	RuntimeCall, RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask,
-	System, EXISTENTIAL_DEPOSIT, SLOT_DURATION, VERSION,
+	Signature, System, DAYS, EXISTENTIAL_DEPOSIT, MICRO_UNIT, MILLI_UNIT, SLOT_DURATION, UNIT,
+	VERSION,
 };
 
 const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
@@ -160,12 +173,106 @@ impl pallet_sudo::Config for Runtime {
 
 parameter_types! {
 	pub const MaxGeohashLength: u32 = 12;
+	// Challenges cover between a city (~39km) and a building (~38m)
+	pub const MinChallengePrecision: u32 = 4;
+	pub const MaxChallengePrecision: u32 = 8;
+	pub const MaxChallengeCells: u32 = 64;
+	// A walk of about an hour from the challenge cell
+	pub const MaxToleranceMetres: u32 = 5_000;
+	pub const MaxChallengesPerBlock: u32 = 64;
+	pub const MaxOracleMetadataLength: u32 = 64;
+	pub const OracleRotationPeriod: BlockNumber = DAYS;
+	pub const MaxAttestations: u32 = 8;
+	// An uncompressed BN254 proof is 256 bytes
+	pub const MaxProofLength: u32 = 256;
+	// Room for an uncompressed key with one public input per geohash character
+	pub const MaxVerifyingKeyLength: u32 = 2048;
+	pub const AttendanceReward: Balance = UNIT;
+	pub const ChallengeDepositBase: Balance = 10 * MILLI_UNIT;
+	// Leaves room above feeless submissions for operational transactions
+	pub const AttendanceUnsignedPriority: TransactionPriority = TransactionPriority::MAX / 2;
 }
 
-pub struct NoMint<T>( PhantomData<T>);
-impl<T> Mintable<T> for NoMint<T> {
-	fn mint(_account: &T) {
-		
+/// The `pallet_nfts` collection holding each challenge's badges.
+///
+/// Collections take the next free id when created, so collections created by root can't claim
+/// the id a later challenge needs.
+#[frame_support::storage_alias]
+pub type BadgeCollections = StorageMap<
+	AttendanceBadges,
+	Twox64Concat,
+	ChallengeId,
+	<Runtime as pallet_nfts::Config>::CollectionId,
+>;
+
+/// Mints attendance badges from a `pallet_nfts` collection created for each challenge.
+///
+/// The collection of a challenge is found in [`BadgeCollections`]. The organizer owns the
+/// collection while the attendance pallet account is its admin, so only accepted submissions can
+/// issue badges.
+///
+/// The organizer, as collection owner, pays each badge's item and metadata deposits, so attendees
+/// without a balance, such as those submitting unsigned, can still receive one. The metadata is
+/// set by the collection admin rather than root, so its deposit is charged like any other.
+pub struct NftBadge;
+impl Mintable<AccountId> for NftBadge {
+	fn create_collection(challenge: ChallengeId, organizer: &AccountId) -> DispatchResult {
+		let collection = <Nfts as Create<_, _>>::create_collection(
+			organizer,
+			&AttendanceBadgeAdmin::get(),
+			&pallet_nfts::CollectionConfigFor::<Runtime>::default(),
+		)?;
+		BadgeCollections::insert(challenge, collection);
+		Ok(())
+	}
+
+	fn mint(
+		account: &AccountId,
+		challenge: ChallengeId,
+		attendee: u32,
+		geohash: &[u8],
+	) -> DispatchResult {
+		let metadata = format!(
+			r#"{{"challenge":{},"geohash":"{}","block":{}}}"#,
+			challenge,
+			core::str::from_utf8(geohash).unwrap_or_default(),
+			System::block_number(),
+		);
+		let collection = BadgeCollections::get(challenge)
+			.ok_or(pallet_nfts::Error::<Runtime>::UnknownCollection)?;
+		<Nfts as Mutate<_, _>>::mint_into(
+			&collection,
+			&attendee,
+			account,
+			&pallet_nfts::ItemConfig::default(),
+			true,
+		)?;
+		<Nfts as Mutate<_, _>>::set_item_metadata(
+			Some(&AttendanceBadgeAdmin::get()),
+			&collection,
+			&attendee,
+			metadata.as_bytes(),
+		)
+	}
+
+	// Badges outlive their challenge, so the collection is kept
+	fn reap_collection(_challenge: ChallengeId, _organizer: &AccountId) -> DispatchResult {
+		Ok(())
+	}
+
+	fn create_collection_weight() -> Weight {
+		<Runtime as pallet_nfts::Config>::WeightInfo::create()
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+
+	fn mint_weight() -> Weight {
+		<Runtime as pallet_nfts::Config>::WeightInfo::mint()
+			.saturating_add(<Runtime as pallet_nfts::Config>::WeightInfo::set_metadata())
+			.saturating_add(RocksDbWeight::get().reads(1))
+	}
+
+	fn reap_collection_weight() -> Weight {
+		Weight::zero()
 	}
 }
 
@@ -175,41 +282,153 @@ impl pallet_attendance::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_attendance::weights::SubstrateWeight<Runtime>;
 	type MaxGeohashLength = MaxGeohashLength;
-	type Mint = NoMint<Self::AccountId>;
+	type MinChallengePrecision = MinChallengePrecision;
+	type MaxChallengePrecision = MaxChallengePrecision;
+	type MaxChallengeCells = MaxChallengeCells;
+	type MaxToleranceMetres = MaxToleranceMetres;
+	type MaxChallengesPerBlock = MaxChallengesPerBlock;
+	type OracleOrigin = EnsureRoot<AccountId>;
+	type MaxOracleMetadataLength = MaxOracleMetadataLength;
+	type OracleRotationPeriod = OracleRotationPeriod;
+	type MaxAttestations = MaxAttestations;
+	type VerifyingKeyOrigin = EnsureRoot<AccountId>;
+	type MaxProofLength = MaxProofLength;
+	type MaxVerifyingKeyLength = MaxVerifyingKeyLength;
+	type UnsignedPriority = AttendanceUnsignedPriority;
+	type Mint = (NftBadge, FungibleReward<Runtime, Balances, AttendanceReward>);
+	type PalletId = AttendancePalletId;
+	type Currency = Balances;
+	type RuntimeHoldReason = RuntimeHoldReason;
+	type ChallengeDepositBase = ChallengeDepositBase;
+	type DepositPerByte = DepositPerByte;
 	type PayloadHasher = sp_runtime::traits::BlakeTwo256;
 	type PublicKeyOfOracle = ed25519::Public;
 	type Signature = ed25519::Signature;
 	type Verify = ed25519::Pair;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = AttendanceBenchmarkHelper;
 }
 
-/*impl pallet_nfts::Config for Runtime {
+/// Signs attestations with oracle keys generated in the benchmark keystore.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct AttendanceBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_attendance::BenchmarkHelper<ed25519::Public, ed25519::Signature>
+	for AttendanceBenchmarkHelper
+{
+	fn sign(index: u32, message: &[u8]) -> (ed25519::Public, ed25519::Signature) {
+		use sp_runtime::{app_crypto::RuntimePublic, KeyTypeId};
+		const ORACLE: KeyTypeId = KeyTypeId(*b"attn");
+		// Generating from the same seed returns the same key
+		let public =
+			ed25519::Public::generate_pair(ORACLE, Some(format!("//Oracle{index}").into_bytes()));
+		let signature = public.sign(ORACLE, &message).expect("key is in the keystore");
+		(public, signature)
+	}
+}
+
+parameter_types! {
+	pub const AttendancePalletId: PalletId = PalletId(*b"py/attnd");
+	pub AttendanceBadgeAdmin: AccountId = AttendancePalletId::get().into_account_truncating();
+	pub Features: PalletFeatures = PalletFeatures::all_enabled();
+	pub const CollectionDeposit: Balance = 10 * MILLI_UNIT;
+	pub const ItemDeposit: Balance = MILLI_UNIT;
+	pub const MetadataDepositBase: Balance = MILLI_UNIT;
+	pub const AttributeDepositBase: Balance = MILLI_UNIT;
+	pub const DepositPerByte: Balance = 10 * MICRO_UNIT;
+}
+
+impl pallet_nfts::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type CollectionId = u32;
+	type CollectionId = ChallengeId;
 	type ItemId = u32;
 	type Currency = Balances;
-	type CreateOrigin = AsEnsureOriginWithArg<frame_system::EnsureSigned<Self::AccountId>>;
-	type ForceOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	// Collections are only created by the attendance pallet, one per challenge
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureNever<AccountId>>;
+	type ForceOrigin = EnsureRoot<AccountId>;
 	type Locker = ();
-	type CollectionDeposit = ConstU64<2>;
-	type ItemDeposit = ConstU64<1>;
-	type MetadataDepositBase = ConstU64<1>;
-	type AttributeDepositBase = ConstU64<1>;
-	type DepositPerByte = ConstU64<1>;
-	type StringLimit = ConstU32<50>;
+	type CollectionDeposit = CollectionDeposit;
+	type ItemDeposit = ItemDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type AttributeDepositBase = AttributeDepositBase;
+	type DepositPerByte = DepositPerByte;
+	type StringLimit = ConstU32<128>;
 	type KeyLimit = ConstU32<50>;
 	type ValueLimit = ConstU32<50>;
 	type ApprovalsLimit = ConstU32<10>;
 	type ItemAttributesApprovalsLimit = ConstU32<2>;
 	type MaxTips = ConstU32<10>;
-	type MaxDeadlineDuration = ConstU64<10000>;
+	type MaxDeadlineDuration = ConstU32<{ 30 * DAYS }>;
 	type MaxAttributesPerCall = ConstU32<2>;
 	type Features = Features;
 	/// Off-chain = signature On-chain - therefore no conversion needed.
 	/// It needs to be From<MultiSignature> for benchmarking.
 	type OffchainSignature = Signature;
 	/// Using `AccountPublic` here makes it trivial to convert to `AccountId` via `into_account()`.
-	type OffchainPublic = AccountPublic;
-	type WeightInfo = ();
+	type OffchainPublic = <Signature as Verify>::Signer;
+	type WeightInfo = pallet_nfts::weights::SubstrateWeight<Runtime>;
 	#[cfg(feature = "runtime-benchmarks")]
 	type Helper = ();
-}*/
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::traits::{fungible, nonfungibles_v2::Inspect};
+	use sp_runtime::BuildStorage;
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let storage = crate::RuntimeGenesisConfig::default()
+			.build_storage()
+			.expect("genesis builds");
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn attendee_without_balance_receives_badge() {
+		new_test_ext().execute_with(|| {
+			let organizer = AccountId::new([1; 32]);
+			let attendee = AccountId::new([2; 32]);
+			<Balances as fungible::Mutate<_>>::set_balance(&organizer, UNIT);
+
+			assert_eq!(NftBadge::create_collection(0, &organizer), Ok(()));
+			assert_eq!(NftBadge::mint(&attendee, 0, 0, b"bcdefg"), Ok(()));
+
+			assert_eq!(BadgeCollections::get(0), Some(0));
+			assert_eq!(<Nfts as Inspect<_>>::owner(&0, &0), Some(attendee.clone()));
+			assert_eq!(Balances::free_balance(&attendee), 0);
+			let metadata = r#"{"challenge":0,"geohash":"bcdefg","block":1}"#;
+			assert_eq!(
+				Balances::reserved_balance(&organizer),
+				CollectionDeposit::get() +
+					ItemDeposit::get() + MetadataDepositBase::get() +
+					DepositPerByte::get() * metadata.len() as Balance
+			);
+		});
+	}
+
+	#[test]
+	fn collection_created_by_root_does_not_block_badges() {
+		new_test_ext().execute_with(|| {
+			let organizer = AccountId::new([1; 32]);
+			let attendee = AccountId::new([2; 32]);
+			<Balances as fungible::Mutate<_>>::set_balance(&organizer, UNIT);
+			assert_eq!(
+				Nfts::force_create(
+					RuntimeOrigin::root(),
+					organizer.clone().into(),
+					pallet_nfts::CollectionConfigFor::<Runtime>::default(),
+				),
+				Ok(())
+			);
+
+			assert_eq!(NftBadge::create_collection(0, &organizer), Ok(()));
+			assert_eq!(BadgeCollections::get(0), Some(1));
+			assert_eq!(NftBadge::mint(&attendee, 0, 0, b"bcdefg"), Ok(()));
+			assert_eq!(<Nfts as Inspect<_>>::owner(&1, &0), Some(attendee));
+		});
+	}
+}
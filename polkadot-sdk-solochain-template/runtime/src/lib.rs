@@ -222,6 +222,6 @@ mod runtime {
 	#[runtime::pallet_index(7)]
 	pub type AttendanceModule = pallet_attendance;
 
-	//#[runtime::pallet_index(8)]
-	//pub type Nfts = pallet_nfts;
+	#[runtime::pallet_index(8)]
+	pub type Nfts = pallet_nfts;
 }